@@ -0,0 +1,235 @@
+//! Every winter, teams with more bases than units may summon new units onto their free home
+//! bases, one for each base they hold over their number of units.
+//!
+//! Teams may also transform their units: simple units are upgraded to super units, as long as the
+//! team is entitled to them, and super units may be downgraded at will. A team is entitled to one
+//! super unit for every 3 bases it holds, and one more for holding all of its home bases. Teams
+//! with more super units than they are entitled to (because they lost bases) have their excess
+//! super units downgraded.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use prelude::game::{
+    order::{IllegalOrder, Order, OrderKind, OrderStatus, Orderable},
+    report::Event,
+    team::Team,
+    unit::Unit,
+    State,
+};
+
+/// Number of units the team may summon: the number of bases it holds over its number of units
+pub fn allowance(state: &State, team: &Team) -> usize {
+    let units = state
+        .units()
+        .iter()
+        .filter(|unit| unit.owner().as_ref() == team)
+        .count();
+    state.bases_held(team).saturating_sub(units)
+}
+
+/// Number of super units the team may have
+pub fn super_entitlement(state: &State, team: &Team) -> usize {
+    state.bases_held(team) / 3 + state.holds_home_bases(team) as usize
+}
+
+/// Number of super units the team has
+fn super_units(state: &State, team: &Team) -> usize {
+    state
+        .units()
+        .iter()
+        .filter(|unit| unit.owner().as_ref() == team && unit.unit_type().is_super())
+        .count()
+}
+
+/// Resolves the summons and transforms, failing every other order.
+///
+/// Downgrades are carried out first, so the super units they give up may be used by upgrades.
+/// Summons and upgrades are then carried out in the order they were given, until each team runs
+/// out of its allowance and entitlement.
+pub fn resolve(state: &State, orders: Vec<OrderKind>) -> Vec<Order> {
+    let mut orders: Vec<Order> = orders.into_iter().map(Order::new).collect();
+
+    for order in orders.iter_mut() {
+        if !matches!(
+            order.order(),
+            OrderKind::Summon(_) | OrderKind::Transform(_)
+        ) {
+            order.reject(IllegalOrder::WrongPhase);
+        }
+    }
+
+    // Units (and regions summoned onto) which already had an order carried out
+    let mut done = HashSet::new();
+    let mut carry_out = |order: &mut Order, resolve: &mut dyn FnMut(&mut Order)| {
+        if order.illegal().is_some() {
+            return;
+        }
+        if done.contains(order.order().unit()) {
+            order.reject(IllegalOrder::Duplicate);
+            return;
+        }
+        resolve(order);
+        if order.status() == OrderStatus::Succeeded {
+            done.insert(order.order().unit().to_owned());
+        }
+    };
+
+    let downgrade = |order: &Order| match order.order() {
+        OrderKind::Transform(transform) => !transform.into_type().is_super(),
+        _ => false,
+    };
+    for order in orders.iter_mut().filter(|order| downgrade(order)) {
+        carry_out(order, &mut |order| {
+            resolve_transform(state, order, &mut HashMap::new())
+        });
+    }
+
+    let mut allowances: HashMap<String, usize> = state
+        .teams()
+        .iter()
+        .map(|team| (team.name().to_owned(), allowance(state, team)))
+        .collect();
+    let mut entitlements: HashMap<String, usize> = state
+        .teams()
+        .iter()
+        .map(|team| {
+            let left = super_entitlement(state, team).saturating_sub(super_units(state, team));
+            (team.name().to_owned(), left)
+        })
+        .collect();
+
+    for order in orders.iter_mut().filter(|order| !downgrade(order)) {
+        carry_out(order, &mut |order| {
+            if let OrderKind::Transform(_) = order.order() {
+                resolve_transform(state, order, &mut entitlements)
+            } else {
+                resolve_summon(state, order, &mut allowances)
+            }
+        });
+    }
+
+    orders
+}
+
+/// Places the summoned unit, if the team has any allowance left
+fn resolve_summon(state: &State, order: &mut Order, allowances: &mut HashMap<String, usize>) {
+    let OrderKind::Summon(summon) = order.order() else {
+        return;
+    };
+    // Summoned units occupy their region right away, so a second summon there is illegal
+    let legality = summon
+        .legal(state)
+        .and_then(|()| match allowances.get_mut(summon.team()) {
+            Some(left) if *left > 0 => {
+                *left -= 1;
+                Ok(())
+            }
+            _ => Err(IllegalOrder::NoBuildsLeft),
+        });
+    match legality {
+        Ok(()) => {
+            let i = state
+                .region_index(summon.unit())
+                .expect("Legal summons are on existing regions");
+            let region = Rc::clone(&state.regions()[i]);
+            let team = state
+                .teams()
+                .iter()
+                .find(|team| team.name() == summon.team())
+                .map(Rc::clone)
+                .expect("Legal summons are by existing teams");
+            state
+                .units_mut()
+                .push(Unit::new(summon.unit_type(), region, team));
+            order.set_status(OrderStatus::Succeeded);
+        }
+        Err(reason) => order.reject(reason),
+    }
+}
+
+/// Transforms the unit, if it is a downgrade, or the team is entitled to one more super unit
+fn resolve_transform(state: &State, order: &mut Order, entitlements: &mut HashMap<String, usize>) {
+    let OrderKind::Transform(transform) = order.order() else {
+        return;
+    };
+    let into = transform.into_type();
+    let legality = transform.legal(state).and_then(|()| {
+        if !into.is_super() {
+            return Ok(());
+        }
+        let team = state
+            .unit_in(transform.unit())
+            .map(|unit| unit.owner().name().to_owned())
+            .expect("Legal transforms are of existing units");
+        match entitlements.get_mut(&team) {
+            Some(left) if *left > 0 => {
+                *left -= 1;
+                Ok(())
+            }
+            _ => Err(IllegalOrder::NoSuperUnitsLeft),
+        }
+    });
+    match legality {
+        Ok(()) => {
+            let region = transform.unit().to_owned();
+            if let Some(unit) = state
+                .units_mut()
+                .iter_mut()
+                .find(|unit| unit.region().name() == region)
+            {
+                unit.transform(into);
+            }
+            order.set_status(OrderStatus::Succeeded);
+        }
+        Err(reason) => order.reject(reason),
+    }
+}
+
+/// Downgrades the super units of teams which have more of them than they are entitled to.
+///
+/// Units are downgraded in the order of their regions' names. Super units which cannot be
+/// downgraded where they are (Planes over Sea) are only chosen once no other is left, and are
+/// disbanded instead.
+pub fn downgrade(state: &State) -> Vec<Event> {
+    let mut events = Vec::new();
+    for team in state.teams() {
+        let excess = super_units(state, team).saturating_sub(super_entitlement(state, team));
+        if excess == 0 {
+            continue;
+        }
+
+        let mut supers: Vec<(bool, String)> = state
+            .units()
+            .iter()
+            .filter(|unit| unit.owner() == team && unit.unit_type().is_super())
+            .map(|unit| {
+                let simple = unit.unit_type().simple();
+                let stranded = !simple.move_terrain().occupies(unit.region().region_type());
+                (stranded, unit.region().name().to_owned())
+            })
+            .collect();
+        supers.sort();
+
+        for (stranded, region) in supers.into_iter().take(excess) {
+            let mut units = state.units_mut();
+            if stranded {
+                units.retain(|unit| unit.region().name() != region);
+                events.push(Event::Disbanded {
+                    team: team.name().to_owned(),
+                    region,
+                });
+            } else if let Some(unit) = units.iter_mut().find(|unit| unit.region().name() == region)
+            {
+                let into = unit.unit_type().simple();
+                unit.transform(into);
+                events.push(Event::Downgraded {
+                    team: team.name().to_owned(),
+                    region,
+                    into,
+                });
+            }
+        }
+    }
+    events
+}
@@ -0,0 +1,24 @@
+//! At the end of fall turns, units standing on bases capture them for their team
+
+use std::rc::Rc;
+
+use prelude::game::{report::Event, State};
+
+/// Gives every base with a unit on it to the unit's team, and reports the bases which changed
+/// hands
+pub fn capture(state: &State) -> Vec<Event> {
+    let mut events = Vec::new();
+    for unit in state.units().iter() {
+        let region = unit.region();
+        let previous = region.owner();
+        if previous.as_ref() == Some(unit.owner()) || !region.capture(Rc::clone(unit.owner())) {
+            continue;
+        }
+        events.push(Event::Captured {
+            team: unit.owner().name().to_owned(),
+            region: region.name().to_owned(),
+            previous: previous.map(|team| team.name().to_owned()),
+        });
+    }
+    events
+}
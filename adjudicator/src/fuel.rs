@@ -0,0 +1,35 @@
+//! Planes which start a turn on a Sea region, and end it on one, run out of fuel, and are removed
+
+use prelude::game::{region::RegionType, report::Event, unit::UnitType, State};
+
+/// Regions of the Planes starting the turn on Sea regions
+pub fn planes_at_sea(state: &State) -> Vec<String> {
+    state
+        .units()
+        .iter()
+        .filter(|unit| unit.unit_type() == UnitType::Plane)
+        .filter(|unit| unit.region().region_type() == RegionType::Sea)
+        .map(|unit| unit.region().name().to_owned())
+        .collect()
+}
+
+/// Removes the Planes which started the turn on Sea regions, and are now on the given regions, if
+/// those are Sea regions as well
+pub fn run_out_of_fuel(state: &State, destinations: Vec<String>) -> Vec<Event> {
+    let mut events = Vec::new();
+    state.units_mut().retain(|unit| {
+        let stranded = unit.unit_type() == UnitType::Plane
+            && unit.region().region_type() == RegionType::Sea
+            && destinations
+                .iter()
+                .any(|region| region == unit.region().name());
+        if stranded {
+            events.push(Event::OutOfFuel {
+                team: unit.owner().name().to_owned(),
+                region: unit.region().name().to_owned(),
+            });
+        }
+        !stranded
+    });
+    events
+}
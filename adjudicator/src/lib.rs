@@ -0,0 +1,151 @@
+//! # Adjudicator
+//!
+//! Once all orders are submitted for a phase of the game, the adjudicator resolves them. In
+//! effect, it is a state transformer, taking the State of one phase, and producing the State of
+//! the next.
+//!
+//! In movement phases, it decides which orders succeed, moves the units, and sets the dislodged
+//! ones aside. After the units have moved, Planes which ran out of fuel are removed.
+//!
+//! In retreat phases, dislodged units retreat, or are disbanded. At the end of fall, units
+//! capture the bases they stand on. Retreat phases without dislodged units are skipped.
+//!
+//! In the winter build phase, teams may summon new units, and transform existing ones.
+//!
+//! After every phase, the standings of the teams are reported, along with whether the game is
+//! over. Once it is, no more orders are accepted.
+//!
+//! Games can be replayed from their recorded orders, to check that they are reproduced exactly.
+
+use std::collections::HashMap;
+
+use prelude::game::{
+    order::{IllegalOrder, Order, OrderKind},
+    report::{Event, Report},
+    State,
+};
+
+pub mod build;
+pub mod capture;
+mod fuel;
+pub mod replay;
+mod resolve;
+mod retreat;
+
+use resolve::Resolver;
+
+/// Resolves the orders of the current phase, and advances the state to the next phase.
+///
+/// The report contains every order with its final status, including the implicit Stay orders of
+/// units which received no (legal) order, and everything else that happened during the phase.
+/// Orders which cannot be given in the current phase, or after the game is over are rejected.
+pub fn adjudicate(state: &mut State, orders: Vec<OrderKind>) -> Report {
+    let teams = teams(state);
+    let standings = state.standings();
+    if standings.is_over() {
+        let orders = orders
+            .into_iter()
+            .map(|order| {
+                let mut order = Order::new(order);
+                order.reject(IllegalOrder::GameOver);
+                order
+            })
+            .collect();
+        let mut report = Report::new(state.turn, state.phase, orders);
+        report.teams = teams;
+        report.standings = Some(standings);
+        return report;
+    }
+
+    let mut report = if state.phase.is_moves() {
+        moves(state, orders)
+    } else if state.phase.is_retreats() {
+        retreats(state, orders)
+    } else {
+        builds(state, orders)
+    };
+    state.advance();
+
+    // Nobody has to retreat, so the retreat phase is over as soon as it begins, even if the
+    // moves ended the game, so that the bases are still captured at the end of fall
+    if state.phase.is_retreats() && state.dislodged().is_empty() {
+        report.events.extend(retreats(state, Vec::new()).events);
+        state.advance();
+    }
+
+    report.teams = teams;
+    report.standings = Some(state.standings());
+    report
+}
+
+/// Team of each unit which may be given an order in the current phase, by its region
+fn teams(state: &State) -> HashMap<String, String> {
+    let units: Vec<_> = if state.phase.is_retreats() {
+        let dislodged = state.dislodged();
+        dislodged
+            .iter()
+            .map(|dislodged| dislodged.unit().clone())
+            .collect()
+    } else {
+        state.units().clone()
+    };
+    units
+        .into_iter()
+        .map(|unit| {
+            (
+                unit.region().name().to_owned(),
+                unit.owner().name().to_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Resolves the orders of a movement phase
+fn moves(state: &State, orders: Vec<OrderKind>) -> Report {
+    let planes_at_sea = fuel::planes_at_sea(state);
+
+    let mut resolver = Resolver::new(state, orders);
+    resolver.resolve();
+    let destinations = planes_at_sea
+        .iter()
+        .filter_map(|region| resolver.destination(region))
+        .collect();
+    let mut report = Report::new(state.turn, state.phase, resolver.apply());
+
+    report
+        .events
+        .extend(state.dislodged().iter().map(|dislodged| Event::Dislodged {
+            team: dislodged.unit().owner().name().to_owned(),
+            region: dislodged.unit().region().name().to_owned(),
+            attacker: dislodged.attacker().to_owned(),
+            retreats: dislodged.retreats().to_vec(),
+        }));
+    report
+        .events
+        .extend(fuel::run_out_of_fuel(state, destinations));
+
+    report
+}
+
+/// Resolves the retreat orders of the units dislodged during the last movement phase, and
+/// disbands the ones which could not retreat. At the end of fall, bases are captured.
+fn retreats(state: &State, orders: Vec<OrderKind>) -> Report {
+    let (orders, events) = retreat::resolve(state, orders);
+    let mut report = Report::new(state.turn, state.phase, orders);
+    report.events = events;
+
+    if state.is_fall() {
+        report.events.extend(capture::capture(state));
+    }
+
+    report
+}
+
+/// Resolves the summon and transform orders of the build phase, then downgrades the super units
+/// teams are no longer entitled to
+fn builds(state: &State, orders: Vec<OrderKind>) -> Report {
+    let mut report = Report::new(state.turn, state.phase, build::resolve(state, orders));
+    report.events.extend(build::downgrade(state));
+
+    report
+}
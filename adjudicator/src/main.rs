@@ -1,3 +1,114 @@
-fn main() {
-    println!("Hello, world!");
+//! Command-line adjudicator
+//!
+//! Reads the state of a game from its store, and the orders of its current phase from a JSON
+//! file (or from the store, if the file is `-`), resolves them, writes the state of the next
+//! phase back to the store, and outputs the phase's report. The report is also printed to the
+//! standard error as text, in the language given by the `HADIJATEK_LANGUAGE` environment variable
+//! (Hungarian by default).
+//!
+//! Usage: `adjudicator <game> <orders.json|-> [report.json]`
+//!
+//! The store is given by the `HADIJATEK_STORE` environment variable:
+//!
+//! - `surrealdb` (the default): the database reached via the `HADIJATEK_DB_ADDRESS`,
+//!   `HADIJATEK_DB_NAMESPACE`, `HADIJATEK_DB_USERNAME`, and `HADIJATEK_DB_PASSWORD` environment
+//!   variables, which also stores the report, and appends the phase to the game's history. The
+//!   address may also be a local database, like `file://games` (with the `file` feature
+//!   enabled); see [`prelude::db::connect`].
+//! - `json:<directory>`: a JSON file in the directory; see [`prelude::db::JsonFile`]
+//!
+//! The .hmap files of the Haskell version (see [`prelude::db::legacy`]) do not list the borders
+//! of their maps, so their games are only imported and exported, and never played.
+
+use std::{env, fs};
+
+use adjudicator::adjudicate;
+use anyhow::{anyhow, Result};
+use prelude::{
+    db::{self, Database, JsonFile, Snapshot, Surrealdb},
+    game::{order::OrderKind, report::Report, State},
+    lang,
+};
+
+const USAGE: &str = "Usage: adjudicator <game> <orders.json|-> [report.json]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let game = args.next().ok_or(anyhow!(USAGE))?;
+    let orders_file = args.next().ok_or(anyhow!(USAGE))?;
+    let orders_file = (orders_file != "-").then_some(orders_file);
+    let report_file = args.next();
+
+    if let Ok(language) = env::var("HADIJATEK_LANGUAGE") {
+        lang::match_set_language(&language)?;
+    }
+
+    let store = env::var("HADIJATEK_STORE").unwrap_or("surrealdb".into());
+    let report = match store.split_once(':') {
+        Some(("json", directory)) => {
+            let mut db = JsonFile::new(game, directory);
+            let (_, _, report, state) = play(&db, orders_file).await?;
+            save(&mut db, &state).await?;
+            report
+        }
+        Some(("legacy", _)) => {
+            return Err(anyhow!(
+                "Legacy maps have no borders, so their games cannot be played"
+            ))
+        }
+        _ if store == "surrealdb" => {
+            let mut db = surrealdb(game).await?;
+            let (before, orders, report, state) = play(&db, orders_file).await?;
+            db.append_history(before, orders, report.clone(), &state)
+                .await?;
+            db.save_orders(&[]).await?;
+            db.write_report(&report).await?;
+            report
+        }
+        _ => return Err(anyhow!("Unknown store: {store}")),
+    };
+
+    let report = serde_json::to_string_pretty(&report)?;
+    match report_file {
+        Some(file) => fs::write(file, report)?,
+        None => println!("{report}"),
+    }
+
+    Ok(())
+}
+
+/// The game in SurrealDB
+async fn surrealdb(game: String) -> Result<Surrealdb> {
+    let address = env::var("HADIJATEK_DB_ADDRESS").unwrap_or("127.0.0.1:8080".into());
+    let namespace = env::var("HADIJATEK_DB_NAMESPACE").unwrap_or("hadijatek".into());
+    let username = env::var("HADIJATEK_DB_USERNAME").unwrap_or("hadijatek".into());
+    let password = env::var("HADIJATEK_DB_PASSWORD").unwrap_or("hadijatek".into());
+    let db = db::connect(&address, &namespace, &username, &password).await?;
+    Ok(Surrealdb::new(game, db))
+}
+
+/// Resolves the orders of the game's current phase, read from the file if there is one, or from
+/// the store otherwise, returning a snapshot of the phase's state, the orders, the report, and
+/// the state of the next phase
+async fn play<D: Database>(
+    db: &D,
+    orders_file: Option<String>,
+) -> Result<(Snapshot, Vec<OrderKind>, Report, State)> {
+    let mut state = db.load_state().await?;
+    let before = Snapshot::new(&state);
+    let orders: Vec<OrderKind> = match orders_file {
+        Some(file) => serde_json::from_str(&fs::read_to_string(file)?)?,
+        None => db.load_orders().await?,
+    };
+
+    let report = adjudicate(&mut state, orders.clone());
+    eprintln!("{}", report.text(lang::get_language()));
+    Ok((before, orders, report, state))
+}
+
+/// Stores the state of the next phase, for which no orders have been given yet
+async fn save<D: Database>(db: &mut D, state: &State) -> Result<()> {
+    db.save_state(state).await?;
+    db.save_orders(&[]).await
 }
@@ -0,0 +1,81 @@
+//! Replaying a game from its beginning, to check that adjudication reproduces it exactly
+//!
+//! Every phase played is recorded with the orders given in it, and the hash of the state they
+//! resulted in. Replaying the orders phase by phase from the state the first of them was given in
+//! must give the same hashes, otherwise the first phase which did not is reported. The history
+//! stored in SurrealDB holds the state before each phase, to replay it from.
+
+use std::{error, fmt};
+
+use prelude::db::HistoryEntry;
+use prelude::game::{order::OrderKind, phase::Phase, State};
+
+use crate::adjudicate;
+
+/// A phase as it was played: the orders given, and the hash of the state they resulted in
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub orders: Vec<OrderKind>,
+    pub hash: u64,
+}
+
+impl From<HistoryEntry> for Step {
+    fn from(entry: HistoryEntry) -> Self {
+        Step {
+            orders: entry.orders,
+            hash: entry.state.hash,
+        }
+    }
+}
+
+/// The first phase whose replay did not result in the recorded state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub turn: usize,
+    pub phase: Phase,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Replay diverged in turn {} ({}): expected state {:016x}, found {:016x}",
+            self.turn, self.phase, self.expected, self.found
+        )
+    }
+}
+
+impl error::Error for Divergence {}
+
+/// Adjudicates the orders, and records them with the resulting state
+pub fn record(state: &mut State, orders: Vec<OrderKind>) -> Step {
+    adjudicate(state, orders.clone());
+    Step {
+        orders,
+        hash: state.canonical_hash(),
+    }
+}
+
+/// Replays the steps on the state the first of them was played in, like the one the game started
+/// in (turn 0), checking the state after each
+pub fn replay<I>(state: &mut State, steps: I) -> Result<(), Divergence>
+where
+    I: IntoIterator<Item = Step>,
+{
+    for step in steps {
+        let (turn, phase) = (state.turn, state.phase);
+        adjudicate(state, step.orders);
+        let found = state.canonical_hash();
+        if found != step.hash {
+            return Err(Divergence {
+                turn,
+                phase,
+                expected: step.hash,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,655 @@
+//! Resolution of a turn's orders
+//!
+//! Every order is a decision, which either succeeds or fails: attacks move, supports and
+//! bombardments hold. Decisions depend on each other (an attack on the strength of its supports,
+//! a support on whether its unit is dislodged by an attack), and are resolved recursively. When
+//! a decision depends on itself, it is guessed to fail, and then to succeed: if both guesses give
+//! the same outcome, that is the outcome. Otherwise the cycle has no single consistent outcome,
+//! and is settled by a backup rule: rings of attacks all move, and any other cycle of orders
+//! fails as a paradox. Orders are resolved in a fixed order, so the outcome does not depend on
+//! the order they were given in.
+//!
+//! Units moving through a region on the way to their target (straits, and the regions Planes and
+//! Submarines move through) must be stronger than any unit of another team holding that region,
+//! otherwise they are blocked, and bounce.
+//!
+//! Bombardments act as phantom defenders of the bombarded region, against both units entering it,
+//! and units moving through it. Like supports, they are cut when the Artillery is attacked.
+//!
+//! Dislodged units are set aside, along with the regions they may retreat to.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use prelude::game::{
+    order::{Attack, Failure, IllegalOrder, Order, OrderKind, OrderStatus, Orderable, Stay},
+    region::Border,
+    team::Team,
+    unit::{Dislodged, Terrain, Unit},
+    State,
+};
+
+pub struct Resolver<'a> {
+    state: &'a State,
+    orders: Vec<Order>,
+    /// Index of the legal order of the unit in each region
+    by_unit: HashMap<String, usize>,
+    /// The unit in each region
+    units: HashMap<String, Unit>,
+    /// How far the decision on each order has come
+    decisions: Vec<Decision>,
+    /// Outcome of each order: final if resolved, a guess if guessing
+    resolutions: Vec<bool>,
+    /// Orders whose outcome depends on a guess
+    dependencies: Vec<usize>,
+    /// Orders failed by the backup rule
+    paradoxes: HashSet<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Unresolved,
+    Guessing,
+    Resolved,
+}
+
+impl<'a> Resolver<'a> {
+    /// Checks the orders, failing illegal ones and ones which cannot be given while moving, and
+    /// gives all units without a legal order an implicit Stay order.
+    pub fn new(state: &'a State, orders: Vec<OrderKind>) -> Self {
+        let units: HashMap<String, Unit> = state
+            .units()
+            .iter()
+            .map(|unit| (unit.region().name().to_owned(), unit.clone()))
+            .collect();
+
+        let mut resolver = Resolver {
+            state,
+            orders: Vec::with_capacity(orders.len()),
+            by_unit: HashMap::new(),
+            units,
+            decisions: Vec::new(),
+            resolutions: Vec::new(),
+            dependencies: Vec::new(),
+            paradoxes: HashSet::new(),
+        };
+
+        for order in orders {
+            let mut order = Order::new(order);
+            let unit = order.order().unit().to_owned();
+            let movement = matches!(
+                order.order(),
+                OrderKind::Stay(_)
+                    | OrderKind::Attack(_)
+                    | OrderKind::Support(_)
+                    | OrderKind::Defend(_)
+                    | OrderKind::Bombard(_)
+            );
+            let legality = if !movement {
+                Err(IllegalOrder::WrongPhase)
+            } else if resolver.by_unit.contains_key(&unit) {
+                Err(IllegalOrder::Duplicate)
+            } else {
+                order.order().legal(state)
+            };
+            match legality {
+                Ok(()) => {
+                    resolver.by_unit.insert(unit, resolver.orders.len());
+                }
+                Err(reason) => order.reject(reason),
+            }
+            resolver.orders.push(order);
+        }
+
+        let mut idle: Vec<String> = resolver
+            .units
+            .keys()
+            .filter(|region| !resolver.by_unit.contains_key(*region))
+            .cloned()
+            .collect();
+        idle.sort();
+        for region in idle {
+            resolver
+                .by_unit
+                .insert(region.clone(), resolver.orders.len());
+            resolver
+                .orders
+                .push(Order::new(OrderKind::Stay(Stay::new(region))));
+        }
+
+        resolver.decisions = vec![Decision::Unresolved; resolver.orders.len()];
+        resolver.resolutions = vec![false; resolver.orders.len()];
+        resolver
+    }
+
+    /// Decides the status of every order
+    pub fn resolve(&mut self) {
+        let mut legal: Vec<usize> = self.by_unit.values().copied().collect();
+        legal.sort();
+
+        for &i in legal.iter() {
+            self.resolve_order(i);
+        }
+
+        for &i in legal.iter() {
+            if let OrderKind::Stay(_) = self.orders[i].order() {
+                continue;
+            }
+            if self.resolutions[i] {
+                self.orders[i].set_status(OrderStatus::Succeeded);
+            } else if self.paradoxes.contains(&i) {
+                self.orders[i].fail(Failure::Paradox);
+            } else {
+                let failure = self
+                    .adjudicate(i)
+                    .expect_err("orders outside paradoxes fail for a reason");
+                self.orders[i].fail(failure);
+            }
+        }
+
+        let dislodged = self.dislodged();
+        for &i in legal.iter() {
+            if let OrderKind::Stay(_) = self.orders[i].order() {
+                if dislodged.contains_key(self.orders[i].order().unit()) {
+                    self.orders[i].fail(Failure::Dislodged);
+                } else {
+                    self.orders[i].set_status(OrderStatus::Succeeded);
+                }
+            }
+        }
+    }
+
+    /// The region the unit in the given region ends the turn on, if it is not dislodged
+    pub fn destination(&self, unit: &str) -> Option<String> {
+        if self.dislodged().contains_key(unit) {
+            return None;
+        }
+        let order = &self.orders[*self.by_unit.get(unit)?];
+        match (order.order(), order.status()) {
+            (OrderKind::Attack(attack), OrderStatus::Succeeded) => Some(attack.target().to_owned()),
+            _ => Some(unit.to_owned()),
+        }
+    }
+
+    /// Moves the units of successful attacks, and sets dislodged units aside to retreat
+    pub fn apply(self) -> Vec<Order> {
+        let dislodged = self.dislodged();
+        let regions = self.state.regions();
+
+        let mut retreating: Vec<Dislodged> = dislodged
+            .iter()
+            .map(|(region, attacker)| {
+                let retreats = self.retreats(region, attacker);
+                Dislodged::new(self.units[region].clone(), attacker.clone(), retreats)
+            })
+            .collect();
+        retreating.sort_by(|a, b| a.unit().region().name().cmp(b.unit().region().name()));
+        self.state.dislodged_mut().extend(retreating);
+
+        let mut units = self.state.units_mut();
+        units.retain(|unit| !dislodged.contains_key(unit.region().name()));
+        for unit in units.iter_mut() {
+            let order = &self.orders[self.by_unit[unit.region().name()]];
+            if let (OrderKind::Attack(attack), OrderStatus::Succeeded) =
+                (order.order(), order.status())
+            {
+                let target = self
+                    .state
+                    .region_index(attack.target())
+                    .expect("legal attacks target existing regions");
+                unit.move_to(Rc::clone(&regions[target]));
+            }
+        }
+        drop(units);
+
+        self.orders
+    }
+
+    /// The legal order of the unit in the region, if there is a unit there
+    fn order_of(&self, region: &str) -> Option<&OrderKind> {
+        self.by_unit.get(region).map(|&i| self.orders[i].order())
+    }
+
+    /// Indices of the legal attacks targeting the region
+    fn attacks_on(&self, region: &str) -> Vec<usize> {
+        let mut attacks: Vec<usize> = self
+            .by_unit
+            .values()
+            .copied()
+            .filter(|&i| match self.orders[i].order() {
+                OrderKind::Attack(attack) => attack.target() == region,
+                _ => false,
+            })
+            .collect();
+        attacks.sort();
+        attacks
+    }
+
+    /// Whether the order succeeds, resolving it if it has not been yet.
+    ///
+    /// Orders which are being decided return their current guess, and are noted as a dependency.
+    /// If the order turns out to depend on its own guess, it is decided by trying both guesses,
+    /// and the backup rule if they disagree.
+    fn resolve_order(&mut self, i: usize) -> bool {
+        match self.decisions[i] {
+            Decision::Resolved => return self.resolutions[i],
+            Decision::Guessing => {
+                // Noted again if it already was, as the order reading the guess depends on it too
+                self.dependencies.push(i);
+                return self.resolutions[i];
+            }
+            Decision::Unresolved => (),
+        }
+
+        let old = self.dependencies.len();
+        self.decisions[i] = Decision::Guessing;
+        self.resolutions[i] = false;
+        let first = self.adjudicate(i).is_ok();
+
+        if self.dependencies.len() == old {
+            // Did not depend on any guess (it may have been settled by the backup rule meanwhile)
+            if self.decisions[i] != Decision::Resolved {
+                self.decisions[i] = Decision::Resolved;
+                self.resolutions[i] = first;
+            }
+            return self.resolutions[i];
+        }
+
+        if self.dependencies[old] != i {
+            // Depends on the guess of an order further up, which will decide this one again
+            self.dependencies.push(i);
+            self.resolutions[i] = first;
+            return first;
+        }
+
+        // Depends on its own guess: try the other one
+        self.forget(old);
+        self.decisions[i] = Decision::Guessing;
+        self.resolutions[i] = true;
+        let second = self.adjudicate(i).is_ok();
+
+        if first == second {
+            self.forget(old);
+            self.decisions[i] = Decision::Resolved;
+            self.resolutions[i] = first;
+            return first;
+        }
+
+        // The cycle may not run through this order, which then depends on it: once it is
+        // settled, the order is decided again, without its stale guess
+        self.backup_rule(old);
+        if self.decisions[i] != Decision::Resolved {
+            self.decisions[i] = Decision::Unresolved;
+        }
+        self.resolve_order(i)
+    }
+
+    /// Discards the guesses of the dependencies noted since the given length
+    fn forget(&mut self, old: usize) {
+        for j in self.dependencies.drain(old..) {
+            self.decisions[j] = Decision::Unresolved;
+        }
+    }
+
+    /// Settles a cycle of orders without a single consistent outcome: rings of attacks all
+    /// succeed, any other cycle fails as a whole
+    fn backup_rule(&mut self, old: usize) {
+        let cycle: Vec<usize> = self.dependencies.drain(old..).collect();
+        let ring = cycle
+            .iter()
+            .all(|&j| matches!(self.orders[j].order(), OrderKind::Attack(_)));
+        for j in cycle {
+            self.decisions[j] = Decision::Resolved;
+            self.resolutions[j] = ring;
+            if !ring {
+                self.paradoxes.insert(j);
+            }
+        }
+    }
+
+    /// Decides the order based on the decisions it depends on
+    fn adjudicate(&mut self, i: usize) -> Result<(), Failure> {
+        match self.orders[i].order() {
+            OrderKind::Attack(_) => self.adjudicate_attack(i),
+            OrderKind::Support(_) | OrderKind::Defend(_) | OrderKind::Bombard(_) => {
+                self.adjudicate_support(i)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Supports hold if the supported order was given, and the supporting unit is neither
+    /// attacked by another team (from anywhere but the region the support is aimed at, by an
+    /// attack which is not stopped on its way), nor dislodged. Bombardments hold if the Artillery
+    /// is neither attacked by another team, nor dislodged.
+    fn adjudicate_support(&mut self, i: usize) -> Result<(), Failure> {
+        let order = self.orders[i].order();
+        let (aimed_at, given) = match order {
+            OrderKind::Support(support) => (
+                Some(support.target().to_owned()),
+                matches!(self.order_of(support.supported()),
+                    Some(OrderKind::Attack(attack)) if attack.target() == support.target()),
+            ),
+            OrderKind::Defend(defend) => (
+                None,
+                !matches!(self.order_of(defend.defended()), Some(OrderKind::Attack(_))),
+            ),
+            OrderKind::Bombard(_) => (None, true),
+            _ => return Err(Failure::Unmatched),
+        };
+        if !given {
+            return Err(Failure::Unmatched);
+        }
+
+        // Attacks stopped on their way never reach the supporting unit
+        let supporter = order.unit().to_owned();
+        let attacks = self.attacks_on(&supporter);
+        for &j in attacks.iter() {
+            let OrderKind::Attack(attack) = self.orders[j].order().clone() else {
+                continue;
+            };
+            let attacker = attack.unit();
+            if self.owner(attacker) == self.owner(&supporter)
+                || Some(attacker) == aimed_at.as_deref()
+            {
+                continue;
+            }
+            let strength = self.attack_strength(j);
+            if self.passage(&attack, strength).is_ok() {
+                return Err(Failure::Cut);
+            }
+        }
+
+        for j in attacks {
+            if self.resolve_order(j) {
+                return Err(Failure::Dislodged);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Owner of the unit in the region
+    fn owner(&self, region: &str) -> Option<&Rc<Team>> {
+        self.units.get(region).map(Unit::owner)
+    }
+
+    /// Strength of the unit in the region when holding, supporting, or bombarding
+    fn strength(&self, region: &str) -> usize {
+        self.units
+            .get(region)
+            .map_or(0, |unit| unit.unit_type().strength())
+    }
+
+    /// Total strength of the units with holding orders matching the predicate
+    fn lent_strength<F>(&mut self, matching: F) -> usize
+    where
+        F: Fn(&OrderKind) -> bool,
+    {
+        let mut lenders: Vec<usize> = self
+            .by_unit
+            .values()
+            .copied()
+            .filter(|&j| matching(self.orders[j].order()))
+            .collect();
+        lenders.sort();
+        let mut lent = 0;
+        for j in lenders {
+            if self.resolve_order(j) {
+                lent += self.strength(self.orders[j].order().unit());
+            }
+        }
+        lent
+    }
+
+    /// Strength of an attack: that of the attacking unit against the target, and its holding
+    /// supports
+    fn attack_strength(&mut self, i: usize) -> usize {
+        let OrderKind::Attack(attack) = self.orders[i].order().clone() else {
+            return 0;
+        };
+        let (Some(unit), Some(from), Some(to)) = (
+            self.units.get(attack.unit()),
+            self.state.region_index(attack.unit()),
+            self.state.region_index(attack.target()),
+        ) else {
+            return 0;
+        };
+        let target = self.state.regions()[to].region_type();
+        let border = attack.via().map_or(self.state.border(from, to), |_| None);
+        let own = unit.unit_type().attack_strength(target, border);
+
+        let supports = self.lent_strength(|order| match order {
+            OrderKind::Support(support) => {
+                support.supported() == attack.unit() && support.target() == attack.target()
+            }
+            _ => false,
+        });
+        own + supports
+    }
+
+    /// Strength with which the unit in the region holds it: its own, and its holding defences
+    fn hold_strength(&mut self, region: &str) -> usize {
+        let defences = self.lent_strength(|order| match order {
+            OrderKind::Defend(defend) => defend.defended() == region,
+            _ => false,
+        });
+        self.strength(region) + defences
+    }
+
+    /// Strength of the holding bombardments of the region
+    fn bombardment(&mut self, region: &str) -> usize {
+        self.lent_strength(|order| match order {
+            OrderKind::Bombard(bombard) => bombard.target() == region,
+            _ => false,
+        })
+    }
+
+    /// Resistance of the unit in the region against units entering or passing through it: none
+    /// if it moves away, its own strength if its attack failed, its hold strength otherwise
+    fn resistance(&mut self, region: &str) -> usize {
+        let Some(j) = self.by_unit.get(region).copied() else {
+            return 0;
+        };
+        if !matches!(self.orders[j].order(), OrderKind::Attack(_)) {
+            self.hold_strength(region)
+        } else if self.resolve_order(j) {
+            0
+        } else {
+            self.strength(region)
+        }
+    }
+
+    /// Strength with which an attack keeps others from entering its target: none if it lost a
+    /// head-to-head battle with the unit in the target, or is stopped on its way
+    fn prevent_strength(&mut self, i: usize) -> usize {
+        let OrderKind::Attack(attack) = self.orders[i].order().clone() else {
+            return 0;
+        };
+        if let Some(&j) = self.by_unit.get(attack.target()) {
+            let head_to_head = matches!(self.orders[j].order(),
+                OrderKind::Attack(other) if other.target() == attack.unit());
+            if head_to_head && self.resolve_order(j) {
+                return 0;
+            }
+        }
+        let strength = self.attack_strength(i);
+        match self.passage(&attack, strength) {
+            Ok(()) => strength,
+            Err(_) => 0,
+        }
+    }
+
+    /// Whether an attack of the given strength makes it past the regions on its way: units of
+    /// other teams holding, and bombardments of those regions contest the passage, without being
+    /// dislodged
+    fn passage(&mut self, attack: &Attack, strength: usize) -> Result<(), Failure> {
+        for region in self.traversed(attack) {
+            let friendly = self.owner(&region) == self.owner(attack.unit());
+            if strength <= self.bombardment(&region) {
+                return Err(Failure::Bombarded(region));
+            } else if !friendly && strength <= self.resistance(&region) {
+                return Err(Failure::Blocked(region));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the attack failed on its way, never reaching its target
+    fn stopped_on_the_way(&self, i: usize) -> bool {
+        let OrderKind::Attack(attack) = self.orders[i].order() else {
+            return false;
+        };
+        match self.orders[i].failure() {
+            Some(Failure::Blocked(_)) => true,
+            Some(Failure::Bombarded(region)) => region != attack.target(),
+            _ => false,
+        }
+    }
+
+    /// Regions the attack passes through on its way to the target: the region it moves via, and
+    /// the straits it crosses, if it is a sea unit (Planes fly over straits)
+    fn traversed(&self, attack: &Attack) -> Vec<String> {
+        let path: Vec<&str> = [Some(attack.unit()), attack.via(), Some(attack.target())]
+            .into_iter()
+            .flatten()
+            .collect();
+        let sea_unit = self
+            .state
+            .unit_in(attack.unit())
+            .is_some_and(|unit| unit.unit_type().move_terrain() == Terrain::Sea);
+        let straits = path.windows(2).filter(|_| sea_unit).filter_map(|step| {
+            let i = self.state.region_index(step[0])?;
+            let j = self.state.region_index(step[1])?;
+            match self.state.border(i, j)? {
+                Border::Strait(strait) => Some(strait.name().to_owned()),
+                _ => None,
+            }
+        });
+        attack
+            .via()
+            .map(str::to_owned)
+            .into_iter()
+            .chain(straits)
+            .collect()
+    }
+
+    /// Decides an attack: it must pass the regions on its way, beat every other attack on its
+    /// target, and the resistance of the target
+    fn adjudicate_attack(&mut self, i: usize) -> Result<(), Failure> {
+        let OrderKind::Attack(attack) = self.orders[i].order().clone() else {
+            return Err(Failure::Bounced);
+        };
+        let target = attack.target();
+        let strength = self.attack_strength(i);
+        self.passage(&attack, strength)?;
+
+        // Head-to-head: the two attacks fight each other
+        let head_to_head = matches!(self.order_of(target),
+            Some(OrderKind::Attack(other)) if other.target() == attack.unit());
+
+        // Units may not dislodge units of their own team
+        if self.owner(target) == self.owner(attack.unit()) {
+            let vacated = match self.by_unit.get(target).copied() {
+                None => true,
+                Some(_) if head_to_head => false,
+                Some(j) => {
+                    matches!(self.orders[j].order(), OrderKind::Attack(_)) && self.resolve_order(j)
+                }
+            };
+            if !vacated {
+                return Err(Failure::Bounced);
+            }
+        }
+
+        // The strongest other attack on the same region
+        let mut competition = 0;
+        for j in self.attacks_on(target) {
+            if j != i {
+                competition = competition.max(self.prevent_strength(j));
+            }
+        }
+
+        let bombardment = self.bombardment(target);
+        let resistance = if head_to_head {
+            self.attack_strength(self.by_unit[target])
+        } else {
+            self.resistance(target)
+        };
+
+        if strength <= bombardment && strength > competition {
+            Err(Failure::Bombarded(target.to_owned()))
+        } else if strength <= competition.max(resistance).max(bombardment) {
+            Err(Failure::Bounced)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Regions whose units stayed, and were beaten by a successful attack, with the region each
+    /// attack came from
+    fn dislodged(&self) -> HashMap<String, String> {
+        self.by_unit
+            .values()
+            .filter_map(|&i| match self.orders[i].order() {
+                OrderKind::Attack(attack) if self.orders[i].status() == OrderStatus::Succeeded => {
+                    Some(attack)
+                }
+                _ => None,
+            })
+            .filter(|attack| match self.by_unit.get(attack.target()) {
+                Some(&j) => !matches!(
+                    (self.orders[j].order(), self.orders[j].status()),
+                    (OrderKind::Attack(_), OrderStatus::Succeeded)
+                ),
+                None => false,
+            })
+            .map(|attack| (attack.target().to_owned(), attack.unit().to_owned()))
+            .collect()
+    }
+
+    /// Regions the unit driven out of the region may retreat to: the neighbors it could move to,
+    /// except for the one the attack came from, the ones occupied after the moves, and the ones
+    /// left empty by a standoff
+    fn retreats(&self, region: &str, attacker: &str) -> Vec<String> {
+        let (Some(unit), Some(i)) = (self.units.get(region), self.state.region_index(region))
+        else {
+            return Vec::new();
+        };
+        let terrain = unit.unit_type().move_terrain();
+        let regions = self.state.regions();
+        let occupied: HashSet<String> = self
+            .units
+            .keys()
+            .filter_map(|region| self.destination(region))
+            .collect();
+        // Attacks stopped on their way never reach the region to stand off there
+        let standoff = |region: &str| {
+            let attacks: Vec<usize> = self
+                .attacks_on(region)
+                .into_iter()
+                .filter(|&j| !self.stopped_on_the_way(j))
+                .collect();
+            !attacks.is_empty()
+                && attacks
+                    .iter()
+                    .all(|&j| self.orders[j].status() != OrderStatus::Succeeded)
+        };
+
+        let mut retreats: Vec<String> = regions
+            .neighbors_slice(i)
+            .iter()
+            .filter(|&&j| {
+                self.state
+                    .border(i, j)
+                    .is_some_and(|border| terrain.crosses(border))
+                    && terrain.occupies(regions[j].region_type())
+            })
+            .map(|&j| regions[j].name().to_owned())
+            .filter(|name| name != attacker && !occupied.contains(name) && !standoff(name))
+            .collect();
+        retreats.sort();
+        retreats.dedup();
+        retreats
+    }
+}
@@ -0,0 +1,89 @@
+//! After the moves, dislodged units retreat to one of the free regions next to the one they were
+//! driven out of. Units retreating to the same region, and units without a (legal) retreat order
+//! are disbanded, as are the units ordered to Kill themselves.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use prelude::game::{
+    order::{Failure, IllegalOrder, Order, OrderKind, OrderStatus, Orderable},
+    report::Event,
+    State,
+};
+
+/// Resolves the retreat and kill orders of the dislodged units, failing every other order, then
+/// puts the retreated units back on the map, and disbands the rest
+pub fn resolve(state: &State, orders: Vec<OrderKind>) -> (Vec<Order>, Vec<Event>) {
+    let mut orders: Vec<Order> = orders.into_iter().map(Order::new).collect();
+
+    let mut ordered = HashSet::new();
+    for order in orders.iter_mut() {
+        let unit = order.order().unit().to_owned();
+        let legality = if !matches!(order.order(), OrderKind::Retreat(_) | OrderKind::Kill(_)) {
+            Err(IllegalOrder::WrongPhase)
+        } else if state.dislodged_from(&unit).is_none() {
+            Err(IllegalOrder::NoUnit(unit.clone()))
+        } else if ordered.contains(&unit) {
+            Err(IllegalOrder::Duplicate)
+        } else {
+            order.order().legal(state)
+        };
+        match legality {
+            Ok(()) => {
+                ordered.insert(unit);
+            }
+            Err(reason) => order.reject(reason),
+        }
+    }
+
+    // Number of legal retreats to each region
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for order in orders.iter().filter(|order| order.illegal().is_none()) {
+        if let OrderKind::Retreat(retreat) = order.order() {
+            *targets.entry(retreat.target().to_owned()).or_default() += 1;
+        }
+    }
+
+    let mut retreated = HashMap::new();
+    for order in orders.iter_mut().filter(|order| order.illegal().is_none()) {
+        match order.order() {
+            OrderKind::Retreat(retreat) if targets[retreat.target()] > 1 => {
+                order.fail(Failure::Bounced);
+            }
+            OrderKind::Retreat(retreat) => {
+                retreated.insert(retreat.unit().to_owned(), retreat.target().to_owned());
+                order.set_status(OrderStatus::Succeeded);
+            }
+            _ => order.set_status(OrderStatus::Succeeded),
+        }
+    }
+
+    let killed: HashSet<String> = orders
+        .iter()
+        .filter(|order| order.status() == OrderStatus::Succeeded)
+        .filter(|order| matches!(order.order(), OrderKind::Kill(_)))
+        .map(|order| order.order().unit().to_owned())
+        .collect();
+
+    let mut events = Vec::new();
+    for dislodged in state.dislodged_mut().drain(..) {
+        let mut unit = dislodged.unit().clone();
+        let region = unit.region().name().to_owned();
+        match retreated
+            .get(&region)
+            .and_then(|target| state.region_index(target))
+        {
+            Some(target) => {
+                unit.move_to(Rc::clone(&state.regions()[target]));
+                state.units_mut().push(unit);
+            }
+            None if killed.contains(&region) => (),
+            None => events.push(Event::Disbanded {
+                team: unit.owner().name().to_owned(),
+                region,
+            }),
+        }
+    }
+
+    (orders, events)
+}
@@ -0,0 +1,422 @@
+//! Adjudicator test cases, written in a small text format
+//!
+//! A case describes a tiny map (teams, regions and borders), places units on it, and then
+//! alternates between giving orders and checking what happened. The format is described in
+//! `tests/cases/README.md`.
+
+use std::{cell::RefCell, rc::Rc};
+
+use petgraph::{csr::Csr, Undirected};
+use prelude::draw::{Color, Point, Shape};
+use prelude::game::{
+    order::{
+        Attack, Bombard, Defend, Failure, Kill, OrderKind, OrderStatus, Orderable, Retreat, Stay,
+        Summon, Support, Transform,
+    },
+    phase::Phase,
+    region::{Base, Border, Region, RegionType},
+    report::Report,
+    team::Team,
+    unit::{Unit, UnitType},
+    victory::{EndConditions, Outcome},
+    State,
+};
+
+/// Runs the case, returning the first line which could not be understood, or whose expectation
+/// was not met
+pub fn run(text: &str) -> Result<(), String> {
+    let mut case = Case::default();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        case.line(&words)
+            .map_err(|error| format!("line {}: {error}", n + 1))?;
+    }
+    case.run()
+}
+
+/// A case being run: the map while it is being described, then the game played on it
+#[derive(Default)]
+struct Case {
+    teams: Vec<Rc<Team>>,
+    regions: Vec<Rc<Region>>,
+    borders: Vec<(u32, u32, Border)>,
+    state: Option<State>,
+    /// Orders given since the last run
+    orders: Vec<OrderKind>,
+    /// Report of the last run
+    report: Option<Report>,
+}
+
+impl Case {
+    fn line(&mut self, words: &[&str]) -> Result<(), String> {
+        match words {
+            ["team", name] => self.team(name),
+            ["region", name, region_type, rest @ ..] => self.region(name, region_type, rest),
+            ["border", from, to, border @ ..] => self.border(from, to, border),
+            ["unit", team, unit_type, region] => self.unit(team, unit_type, region),
+            ["phase", season, kind] => {
+                self.state().phase = phase(season, kind)?;
+                Ok(())
+            }
+            ["turn", turn] => {
+                self.state().turn = number(turn)?;
+                Ok(())
+            }
+            ["end", condition @ ..] => self.end(condition),
+            ["order", order @ ..] => {
+                let order = order_kind(order)?;
+                self.state();
+                self.orders.push(order);
+                Ok(())
+            }
+            ["run"] => self.run(),
+            ["expect", expectation @ ..] => {
+                if !self.orders.is_empty() {
+                    self.run()?;
+                }
+                self.expect(expectation)
+            }
+            _ => Err(format!("cannot understand \"{}\"", words.join(" "))),
+        }
+    }
+
+    /// The game, set up on the map described so far, in spring, without any end conditions
+    fn state(&mut self) -> &mut State {
+        self.state.get_or_insert_with(|| {
+            let mut regions: Csr<Rc<Region>, Border, Undirected> = Csr::new();
+            for region in self.regions.iter() {
+                regions.add_node(Rc::clone(region));
+            }
+            for (from, to, border) in self.borders.iter() {
+                regions.add_edge(*from, *to, border.clone());
+            }
+            let mut state = State::new(self.teams.clone(), regions, Color::black(), Color::black());
+            state.phase = Phase::SpringMoves;
+            state.end_conditions = EndConditions {
+                turn_limit: None,
+                base_majority: None,
+                elimination: false,
+            };
+            state
+        })
+    }
+
+    /// Adjudicates the orders given since the last run
+    fn run(&mut self) -> Result<(), String> {
+        let orders = std::mem::take(&mut self.orders);
+        self.report = Some(adjudicator::adjudicate(self.state(), orders));
+        Ok(())
+    }
+
+    fn describing_map(&self) -> Result<(), String> {
+        match self.state {
+            None => Ok(()),
+            Some(_) => Err("the map must be described before units and orders".to_owned()),
+        }
+    }
+
+    fn team_named(&self, name: &str) -> Result<Rc<Team>, String> {
+        self.teams
+            .iter()
+            .find(|team| team.name() == name)
+            .map(Rc::clone)
+            .ok_or_else(|| format!("no team called {name}"))
+    }
+
+    fn region_index(&self, name: &str) -> Result<u32, String> {
+        self.regions
+            .iter()
+            .position(|region| region.name() == name)
+            .map(|i| i as u32)
+            .ok_or_else(|| format!("no region called {name}"))
+    }
+
+    fn team(&mut self, name: &str) -> Result<(), String> {
+        self.describing_map()?;
+        if self.team_named(name).is_ok() {
+            return Err(format!("team {name} already exists"));
+        }
+        // Teams get distinct colors, so their home bases can be told apart
+        let color = Color::new(0, 0, self.teams.len() as u8 + 1);
+        self.teams.push(Rc::new(Team::new(name.to_owned(), color)));
+        Ok(())
+    }
+
+    fn region(&mut self, name: &str, region_type: &str, rest: &[&str]) -> Result<(), String> {
+        self.describing_map()?;
+        if self.region_index(name).is_ok() {
+            return Err(format!("region {name} already exists"));
+        }
+        let region_type = match region_type {
+            "land" => RegionType::Land,
+            "shore" => RegionType::Shore,
+            "strait" => RegionType::Strait,
+            "sea" => RegionType::Sea,
+            other => return Err(format!("no region type called {other}")),
+        };
+
+        let (base, owner, color) = match rest {
+            [] => (false, None, Color::white()),
+            ["base"] => (true, None, Color::white()),
+            ["base", owner] => (true, Some(*owner), Color::white()),
+            ["home", team] => (true, Some(*team), self.team_named(team)?.color()),
+            ["home", team, owner] => (true, Some(*owner), self.team_named(team)?.color()),
+            _ => return Err(format!("cannot understand \"{}\"", rest.join(" "))),
+        };
+        let base = base.then(|| {
+            let mut base = Base::new();
+            if let Some(owner) = owner.filter(|&owner| owner != "none") {
+                base.set(self.team_named(owner)?);
+            }
+            Ok::<_, String>(RefCell::new(base))
+        });
+        let region = Region::new(
+            name.to_owned(),
+            region_type,
+            base.transpose()?,
+            Shape::new(&[]),
+            Point::new(0.0, 0.0),
+            color,
+        )
+        .map_err(|error| error.to_string())?;
+        self.regions.push(Rc::new(region));
+        Ok(())
+    }
+
+    fn border(&mut self, from: &str, to: &str, border: &[&str]) -> Result<(), String> {
+        self.describing_map()?;
+        let (from, to) = (self.region_index(from)?, self.region_index(to)?);
+        let border = match border {
+            ["land"] => Border::Land,
+            ["shore"] => Border::Shore,
+            ["sea"] => Border::Sea,
+            ["strait", strait] => Border::Strait(Rc::clone(
+                &self.regions[self.region_index(strait)? as usize],
+            )),
+            _ => return Err(format!("no border type called \"{}\"", border.join(" "))),
+        };
+        self.borders.push((from, to, border));
+        Ok(())
+    }
+
+    fn unit(&mut self, team: &str, unit_type: &str, region: &str) -> Result<(), String> {
+        let (team, unit_type) = (self.team_named(team)?, self::unit_type(unit_type)?);
+        let region = Rc::clone(&self.regions[self.region_index(region)? as usize]);
+        let state = self.state();
+        if state.unit_in(region.name()).is_some() {
+            return Err(format!("there already is a unit in {}", region.name()));
+        }
+        state.units_mut().push(Unit::new(unit_type, region, team));
+        Ok(())
+    }
+
+    fn end(&mut self, condition: &[&str]) -> Result<(), String> {
+        let end_conditions = &mut self.state().end_conditions;
+        match condition {
+            ["elimination"] => end_conditions.elimination = true,
+            ["turns", turns] => end_conditions.turn_limit = Some(number(turns)?),
+            ["bases", bases] => end_conditions.base_majority = Some(number(bases)?),
+            _ => return Err(format!("no end condition \"{}\"", condition.join(" "))),
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expectation: &[&str]) -> Result<(), String> {
+        if let ["order", unit, outcome @ ..] = expectation {
+            return self.expect_order(unit, outcome);
+        }
+        let state = self.state();
+        match expectation {
+            ["unit", team, unit_type, region] => {
+                let unit_type = self::unit_type(unit_type)?;
+                let found = state.unit_in(region).map(|unit| {
+                    (unit.owner().name().to_owned(), unit.unit_type())
+                        == (team.to_string(), unit_type)
+                });
+                match found {
+                    Some(true) => Ok(()),
+                    Some(false) => Err(format!("{region} holds a different unit")),
+                    None => Err(format!("{region} is empty")),
+                }
+            }
+            ["empty", region] => match state.unit_in(region) {
+                None => Ok(()),
+                Some(unit) => Err(format!(
+                    "{region} holds a {:?} of {}",
+                    unit.unit_type(),
+                    unit.owner().name()
+                )),
+            },
+            ["units", count] => {
+                let (count, found) = (number(count)?, state.units().len());
+                expected("units", count, found)
+            }
+            ["dislodged", team, unit_type, region, retreats @ ..] => {
+                let unit_type = self::unit_type(unit_type)?;
+                let dislodged = state
+                    .dislodged_from(region)
+                    .ok_or_else(|| format!("no unit was dislodged from {region}"))?;
+                let unit = dislodged.unit();
+                if (unit.owner().name().as_str(), unit.unit_type()) != (*team, unit_type) {
+                    return Err(format!("a different unit was dislodged from {region}"));
+                }
+                match retreats {
+                    [] => Ok(()),
+                    ["retreats", retreats @ ..] => {
+                        let retreats: Vec<String> =
+                            retreats.iter().map(|region| region.to_string()).collect();
+                        expected("retreats", retreats, dislodged.retreats().to_vec())
+                    }
+                    _ => Err(format!("cannot understand \"{}\"", retreats.join(" "))),
+                }
+            }
+            ["owner", region, owner] => {
+                let i = state
+                    .region_index(region)
+                    .ok_or_else(|| format!("no region called {region}"))?;
+                let found = state.regions()[i]
+                    .owner()
+                    .map_or("none".to_owned(), |team| team.name().to_owned());
+                expected("owner", owner.to_string(), found)
+            }
+            ["phase", season, kind] => expected("phase", phase(season, kind)?, state.phase),
+            ["turn", turn] => expected("turn", number(turn)?, state.turn),
+            ["ongoing"] => expected("outcome", Outcome::Ongoing, state.standings().outcome),
+            ["winner", team] => expected(
+                "outcome",
+                Outcome::Won(team.to_string()),
+                state.standings().outcome,
+            ),
+            ["draw", teams @ ..] => {
+                let mut teams: Vec<String> = teams.iter().map(|team| team.to_string()).collect();
+                let found = match state.standings().outcome {
+                    Outcome::Drawn(mut drawn) => {
+                        teams.sort();
+                        drawn.sort();
+                        Outcome::Drawn(drawn)
+                    }
+                    outcome => outcome,
+                };
+                expected("outcome", Outcome::Drawn(teams), found)
+            }
+            _ => Err(format!("cannot understand \"{}\"", expectation.join(" "))),
+        }
+    }
+
+    /// Checks the outcome of the first order given to the unit in the last run
+    fn expect_order(&self, unit: &str, outcome: &[&str]) -> Result<(), String> {
+        let report = self.report.as_ref().ok_or("no orders were run yet")?;
+        let order = report
+            .orders
+            .iter()
+            .find(|order| order.order().unit() == unit)
+            .ok_or_else(|| format!("{unit} was not given an order"))?;
+        let found = format!(
+            "{:?}, failure: {:?}, illegal: {:?}",
+            order.status(),
+            order.failure(),
+            order.illegal()
+        );
+        let met = match outcome {
+            ["succeeds"] => order.status() == OrderStatus::Succeeded,
+            ["illegal"] => order.illegal().is_some(),
+            failure => order.failure() == Some(&self::failure(failure)?),
+        };
+        if met {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected the order of {unit} to be {}, found {found}",
+                outcome.join(" ")
+            ))
+        }
+    }
+}
+
+fn expected<T: PartialEq + std::fmt::Debug>(
+    what: &str,
+    expected: T,
+    found: T,
+) -> Result<(), String> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(format!("expected {what} {expected:?}, found {found:?}"))
+    }
+}
+
+fn number(word: &str) -> Result<usize, String> {
+    word.parse().map_err(|_| format!("{word} is not a number"))
+}
+
+fn unit_type(word: &str) -> Result<UnitType, String> {
+    use UnitType::*;
+    Ok(match word {
+        "tank" => Tank,
+        "ship" => Ship,
+        "plane" => Plane,
+        "supertank" => Supertank,
+        "submarine" => Submarine,
+        "artillery" => Artillery,
+        other => return Err(format!("no unit type called {other}")),
+    })
+}
+
+fn phase(season: &str, kind: &str) -> Result<Phase, String> {
+    use Phase::*;
+    Ok(match (season, kind) {
+        ("spring", "moves") => SpringMoves,
+        ("spring", "retreats") => SpringRetreats,
+        ("fall", "moves") => FallMoves,
+        ("fall", "retreats") => FallRetreats,
+        ("winter", "builds") => WinterBuilds,
+        _ => return Err(format!("no phase called {season} {kind}")),
+    })
+}
+
+fn failure(words: &[&str]) -> Result<Failure, String> {
+    use Failure::*;
+    Ok(match words {
+        ["bounced"] => Bounced,
+        ["blocked", region] => Blocked(region.to_string()),
+        ["bombarded", region] => Bombarded(region.to_string()),
+        ["cut"] => Cut,
+        ["unmatched"] => Unmatched,
+        ["dislodged"] => Dislodged,
+        ["paradox"] => Paradox,
+        _ => return Err(format!("no outcome called \"{}\"", words.join(" "))),
+    })
+}
+
+fn order_kind(words: &[&str]) -> Result<OrderKind, String> {
+    let owned = |word: &&str| word.to_string();
+    Ok(match words {
+        [unit, "stays"] => OrderKind::Stay(Stay::new(owned(unit))),
+        [unit, "attacks", target] => OrderKind::Attack(Attack::new(owned(unit), owned(target))),
+        [unit, "attacks", target, "via", via] => {
+            OrderKind::Attack(Attack::through(owned(unit), owned(via), owned(target)))
+        }
+        [unit, "supports", supported, "into", target] => {
+            OrderKind::Support(Support::new(owned(unit), owned(supported), owned(target)))
+        }
+        [unit, "defends", defended] => OrderKind::Defend(Defend::new(owned(unit), owned(defended))),
+        [unit, "bombards", target] => OrderKind::Bombard(Bombard::new(owned(unit), owned(target))),
+        [unit, "retreats", "to", target] => {
+            OrderKind::Retreat(Retreat::new(owned(unit), owned(target)))
+        }
+        [unit, "disbands"] => OrderKind::Kill(Kill::new(owned(unit))),
+        [team, "summons", unit_type, "in", region] => OrderKind::Summon(Summon::new(
+            owned(team),
+            self::unit_type(unit_type)?,
+            owned(region),
+        )),
+        [unit, "transforms", "into", unit_type] => {
+            OrderKind::Transform(Transform::new(owned(unit), self::unit_type(unit_type)?))
+        }
+        _ => return Err(format!("no order \"{}\"", words.join(" "))),
+    })
+}
@@ -0,0 +1,36 @@
+//! Runs every test case in `tests/cases`
+
+mod case;
+
+use std::{fs, panic, path::Path};
+
+#[test]
+fn cases() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .expect("the test cases are in tests/cases")
+        .map(|entry| entry.expect("test cases can be listed").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "case")
+        })
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no test cases found");
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let text = fs::read_to_string(&path).expect("test cases are readable text files");
+        match panic::catch_unwind(|| case::run(&text)) {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => failures.push(format!("{name}, {error}")),
+            Err(_) => failures.push(format!("{name}: the adjudicator panicked")),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "failed cases:\n{}",
+        failures.join("\n")
+    );
+}
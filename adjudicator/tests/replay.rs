@@ -0,0 +1,137 @@
+//! Replaying recorded games
+
+use std::{cell::RefCell, rc::Rc};
+
+use adjudicator::adjudicate;
+use adjudicator::replay::{record, replay, Step};
+use petgraph::{csr::Csr, Undirected};
+use prelude::db::{self, Snapshot, Surrealdb};
+use prelude::draw::{Color, Point, Shape};
+use prelude::game::{
+    order::{Attack, OrderKind, Summon},
+    phase::Phase,
+    region::{Base, Border, Region, RegionType},
+    team::Team,
+    unit::UnitType,
+    State,
+};
+
+/// Two teams, each with a home base, on either end of a line of land regions
+fn game() -> State {
+    let red = Rc::new(Team::new("red".into(), Color::new(255, 0, 0)));
+    let blue = Rc::new(Team::new("blue".into(), Color::new(0, 0, 255)));
+    let mut regions: Csr<Rc<Region>, Border, Undirected> = Csr::new();
+    for (name, home) in [
+        ("a", Some(&red)),
+        ("b", None),
+        ("c", None),
+        ("d", Some(&blue)),
+    ] {
+        let base = home.map(|team| {
+            let mut base = Base::new();
+            base.set(Rc::clone(team));
+            RefCell::new(base)
+        });
+        let color = home.map_or(Color::white(), |team| team.color());
+        let region = Region::new(
+            name.into(),
+            RegionType::Land,
+            base,
+            Shape::new(&[]),
+            Point::new(0.0, 0.0),
+            color,
+        )
+        .expect("land regions may have bases");
+        regions.add_node(Rc::new(region));
+    }
+    for i in 0..3 {
+        regions.add_edge(i, i + 1, Border::Land);
+    }
+    State::new(vec![red, blue], regions, Color::black(), Color::black())
+}
+
+fn orders() -> Vec<Vec<OrderKind>> {
+    let summon = |team: &str, region: &str| {
+        OrderKind::Summon(Summon::new(team.into(), UnitType::Tank, region.into()))
+    };
+    let attack = |from: &str, to: &str| OrderKind::Attack(Attack::new(from.into(), to.into()));
+    vec![
+        vec![summon("red", "a"), summon("blue", "d")],
+        vec![attack("a", "b"), attack("d", "c")],
+        vec![attack("b", "c"), attack("c", "b")],
+        vec![],
+    ]
+}
+
+#[test]
+fn replay_reproduces_game() {
+    let mut played = game();
+    let steps: Vec<_> = orders()
+        .into_iter()
+        .map(|orders| record(&mut played, orders))
+        .collect();
+    assert_eq!((played.turn, played.phase), (2, Phase::SpringMoves));
+
+    let mut replayed = game();
+    assert_eq!(replay(&mut replayed, steps), Ok(()));
+    assert_eq!(replayed.canonical_hash(), played.canonical_hash());
+}
+
+#[test]
+fn replay_reports_first_divergence() {
+    let mut played = game();
+    let mut steps: Vec<_> = orders()
+        .into_iter()
+        .map(|orders| record(&mut played, orders))
+        .collect();
+    steps[1].orders.pop();
+
+    let mut replayed = game();
+    let divergence = replay(&mut replayed, steps).expect_err("blue did not move");
+    assert_eq!(
+        (divergence.turn, divergence.phase),
+        (0, Phase::SpringMoves),
+        "{divergence}"
+    );
+}
+
+#[tokio::test]
+async fn replay_from_history() -> anyhow::Result<()> {
+    let db = db::connect("mem://", "hadijatek", "hadijatek", "hadijatek").await?;
+    let mut db = Surrealdb::new("test_replay_from_history".into(), db);
+    let mut played = game();
+    db.write(&played).await?;
+    for orders in orders() {
+        let before = Snapshot::new(&played);
+        let report = adjudicate(&mut played, orders.clone());
+        db.append_history(before, orders, report, &played).await?;
+    }
+
+    let history = db.read_history().await?;
+    assert_eq!(history.len(), 4);
+    // The game as it is stored now, put back in the state it started in
+    let mut replayed = db.read().await?;
+    history[0].before.clone().restore(&mut replayed)?;
+    assert_eq!(replayed.canonical_hash(), game().canonical_hash());
+
+    let steps = history.into_iter().map(Step::from);
+    assert_eq!(replay(&mut replayed, steps), Ok(()));
+    assert_eq!(replayed.canonical_hash(), played.canonical_hash());
+
+    Ok(())
+}
+
+#[test]
+fn hash_ignores_storage_order() {
+    let (mut first, mut second) = (game(), game());
+    let mut summons = orders().remove(0);
+    record(&mut first, summons.clone());
+    summons.reverse();
+    record(&mut second, summons);
+    assert_ne!(
+        first.units()[0].region().name(),
+        second.units()[0].region().name()
+    );
+    assert_eq!(first.canonical_hash(), second.canonical_hash());
+    assert_ne!(first.canonical_hash(), game().canonical_hash());
+}
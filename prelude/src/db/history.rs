@@ -0,0 +1,113 @@
+//! The history of a game: a record of every phase played, which is only ever added to
+//!
+//! Each record holds the orders given in the phase, the report of their adjudication, and the
+//! states the game was in before and after it, so that past turns can be shown, and games can be
+//! replayed from any phase.
+
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use petgraph::visit::IntoNodeReferences;
+use serde::{Deserialize, Serialize};
+
+use super::{add_units, serialize_units, SerializedUnit, Surrealdb};
+use crate::game::{order::OrderKind, phase::Phase, report::Report, State};
+
+/// A phase as it was played
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub orders: Vec<OrderKind>,
+    pub report: Report,
+    /// The state the phase was played in
+    pub before: Snapshot,
+    /// The state after the phase
+    pub state: Snapshot,
+}
+
+/// The parts of a state which change as the game is played
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub turn: usize,
+    pub phase: Phase,
+    /// Owner of each base, by region
+    pub owners: Vec<(String, Option<String>)>,
+    pub units: Vec<SerializedUnit>,
+    /// Canonical hash of the state
+    pub hash: u64,
+}
+
+impl Snapshot {
+    pub fn new(state: &State) -> Self {
+        let owners = state
+            .regions()
+            .node_references()
+            .filter(|(_, region)| region.has_base())
+            .map(|(_, region)| {
+                let owner = region.owner().map(|team| team.name().to_owned());
+                (region.name().to_owned(), owner)
+            })
+            .collect();
+        Snapshot {
+            turn: state.turn,
+            phase: state.phase,
+            owners,
+            units: serialize_units(state),
+            hash: state.canonical_hash(),
+        }
+    }
+
+    /// Puts the game back in the snapshot's state. The map, the teams, and the end conditions are
+    /// those of the given state, as they do not change as the game is played.
+    pub fn restore(self, state: &mut State) -> Result<()> {
+        state.turn = self.turn;
+        state.phase = self.phase;
+        for (region, owner) in self.owners {
+            let index = state
+                .region_index(&region)
+                .ok_or(anyhow!("Region {region} not found"))?;
+            let region = &state.regions()[index];
+            match owner {
+                Some(owner) => {
+                    let team = state
+                        .teams()
+                        .iter()
+                        .find(|team| team.name() == &owner)
+                        .ok_or(anyhow!("Team {owner} not found"))?;
+                    region.capture(Rc::clone(team));
+                }
+                None => region.release(),
+            }
+        }
+        state.units_mut().clear();
+        state.dislodged_mut().clear();
+        add_units(state, self.units)
+    }
+}
+
+impl Surrealdb {
+    /// Writes the state of the next phase, and adds the phase just played, from the snapshot taken
+    /// before it, to the end of the history, in a single transaction. Records of past phases are never overwritten: creating a
+    /// record which already exists fails, and the state is not written either.
+    pub async fn append_history(
+        &mut self,
+        before: Snapshot,
+        orders: Vec<OrderKind>,
+        report: Report,
+        state: &State,
+    ) -> Result<()> {
+        let entry = HistoryEntry {
+            orders,
+            report,
+            before,
+            state: Snapshot::new(state),
+        };
+        self.write_transaction(state, Some(entry)).await
+    }
+
+    /// Every phase played so far, in the order they were played
+    pub async fn read_history(&self) -> Result<Vec<HistoryEntry>> {
+        let db = self.connect().await?;
+        let mut response = db.query("SELECT * FROM history ORDER BY id;").await?;
+        Ok(response.take(0)?)
+    }
+}
@@ -0,0 +1,166 @@
+//! Games stored in plain JSON files
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use petgraph::csr::Csr;
+use petgraph::visit::{EdgeRef, IntoNodeReferences};
+use petgraph::Undirected;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    add_units, deserialize_border, deserialize_region, read_orders, serialize_border,
+    serialize_region, serialize_units, write_orders, Database, Prelude, SerializedBorder,
+    SerializedRegion, SerializedUnit,
+};
+use crate::game::{
+    order::OrderKind,
+    region::{Border, Region},
+    team::Team,
+    State,
+};
+
+/// A game stored in `<name>.json` in a directory, with the orders given for its current phase in
+/// `<name>.orders.json`
+pub struct JsonFile {
+    name: String,
+    directory: PathBuf,
+}
+
+/// The contents of a game's file
+#[derive(Debug, Serialize, Deserialize)]
+struct Game {
+    prelude: Prelude,
+    teams: Vec<Rc<Team>>,
+    regions: Vec<SerializedRegion>,
+    borders: Vec<SavedBorder>,
+    units: Vec<SerializedUnit>,
+}
+
+/// A border, listed once, between the regions of the given names
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedBorder {
+    from: String,
+    to: String,
+    #[serde(flatten)]
+    border: SerializedBorder,
+}
+
+impl JsonFile {
+    pub fn new(name: String, directory: impl Into<PathBuf>) -> Self {
+        JsonFile {
+            name,
+            directory: directory.into(),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.directory.join(format!("{}.json", self.name))
+    }
+
+    fn orders_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.orders.json", self.name))
+    }
+}
+
+impl Game {
+    fn new(state: &State) -> Self {
+        let map = state.regions();
+        let mut borders = Vec::new();
+        for (i, from) in map.node_references() {
+            for edge in map.edges(i).filter(|edge| i < edge.target()) {
+                borders.push(SavedBorder {
+                    from: from.name().to_owned(),
+                    to: map[edge.target()].name().to_owned(),
+                    border: serialize_border(edge.weight()),
+                });
+            }
+        }
+
+        Game {
+            prelude: Prelude::new(state),
+            teams: state.teams().to_vec(),
+            regions: map
+                .node_references()
+                .map(|(_, region)| serialize_region(region))
+                .collect(),
+            borders,
+            units: serialize_units(state),
+        }
+    }
+
+    fn into_state(self) -> Result<State> {
+        let mut regions: Csr<Rc<Region>, Border, Undirected> = Csr::new();
+        let mut indices: HashMap<String, u32> = HashMap::new();
+        for region in self.regions {
+            let region = deserialize_region(region, &self.teams)?;
+            let name = region.name().to_owned();
+            indices.insert(name, regions.add_node(Rc::new(region)));
+        }
+
+        for SavedBorder { from, to, border } in self.borders {
+            let index = |name: &str| {
+                indices
+                    .get(name)
+                    .copied()
+                    .ok_or(anyhow!("Region {} not found", name))
+            };
+            let (i, j) = (index(&from)?, index(&to)?);
+            let border = deserialize_border(border, &regions)?;
+            regions.add_edge(i, j, border);
+        }
+
+        let prelude = self.prelude;
+        let mut state = State::new(
+            self.teams,
+            regions,
+            prelude.water_stroke,
+            prelude.land_stroke,
+        );
+        state.turn = prelude.turn;
+        state.phase = prelude.phase;
+        state.end_conditions = prelude.end_conditions;
+        add_units(&mut state, self.units)?;
+        Ok(state)
+    }
+}
+
+impl Database for JsonFile {
+    async fn load_state(&self) -> Result<State> {
+        let game: Game = serde_json::from_str(&fs::read_to_string(self.path())?)?;
+        game.into_state()
+    }
+
+    async fn save_state(&mut self, state: &State) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let game = serde_json::to_string_pretty(&Game::new(state))?;
+        fs::write(self.path(), game)?;
+        Ok(())
+    }
+
+    async fn load_orders(&self) -> Result<Vec<OrderKind>> {
+        read_orders(&self.orders_path())
+    }
+
+    async fn save_orders(&mut self, orders: &[OrderKind]) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        write_orders(&self.orders_path(), orders)
+    }
+
+    async fn list_games(&self) -> Result<Vec<String>> {
+        let mut games = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if let Some(game) = name.strip_suffix(".json") {
+                if !game.ends_with(".orders") {
+                    games.push(game.to_owned());
+                }
+            }
+        }
+        games.sort();
+        Ok(games)
+    }
+}
@@ -0,0 +1,50 @@
+use std::{error, fmt};
+
+/// An error in a .hmap file, on the given line (counted from 1)
+#[derive(Debug, PartialEq)]
+pub struct LegacyError {
+    pub line: usize,
+    pub kind: LegacyErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegacyErrorKind {
+    /// A line which does not belong where it is
+    Unknown(String),
+    /// A line which is not what its section holds, which is described
+    Malformed(&'static str),
+    /// Entries are numbered in order, from 0
+    Index {
+        expected: usize,
+        found: usize,
+    },
+    NoTeam(usize),
+    NoRegion(usize),
+    NoBase(String),
+    /// A section or line which is not there
+    Missing(&'static str),
+}
+
+impl LegacyError {
+    pub fn new(line: usize, kind: LegacyErrorKind) -> Self {
+        LegacyError { line, kind }
+    }
+}
+
+impl fmt::Display for LegacyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use LegacyErrorKind::*;
+        write!(f, "Line {}: ", self.line)?;
+        match &self.kind {
+            Unknown(line) => write!(f, "unknown line: {line}"),
+            Malformed(expected) => write!(f, "malformed line, expected {expected}"),
+            Index { expected, found } => write!(f, "entry {found} should be entry {expected}"),
+            NoTeam(team) => write!(f, "there is no team {team}"),
+            NoRegion(region) => write!(f, "there is no region {region}"),
+            NoBase(region) => write!(f, "{region} has no base"),
+            Missing(what) => write!(f, "{what} is missing"),
+        }
+    }
+}
+
+impl error::Error for LegacyError {}
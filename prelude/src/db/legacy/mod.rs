@@ -1,58 +1,155 @@
 //! Legacy .hmap files
+//!
+//! The files of the Haskell version, in four sections, separated by `---` lines:
+//!
+//! - the prelude: the name of the game, the number of steps (turns), and the stroke colors of
+//!   water and land, each on its own `label: value` line, labelled in Hungarian or English
+//! - teams: `index,"name","#color",[(region,True|False),...]`, listing the indices of the
+//!   regions of the team's bases, and whether each is a home base
+//! - units: `index,type,team,region`, with the unit type's code (0 to 5: Tank, Ship, Plane,
+//!   Supertank, Submarine, Artillery), and the indices of the unit's team and region
+//! - regions: `index,type,"name","#color",(x,y),"M x,y M x,y ... Z"`, where the type is 0 for
+//!   sea, 1 for land, and 2 for land with a base, followed by the pole, and the shape
+//!
+//! Entries are numbered from 0, in order. Legacy maps do not tell shores and straits from land,
+//! and do not list the borders between regions.
+//!
+//! Each turn of a game is stored in its own file, `<name>_<turn>.hmap`, and the orders given for
+//! its current phase in `<name>.orders.json`, as the files have no place for them.
 
+use std::collections::BTreeSet;
 use std::fs;
+use std::path::PathBuf;
 use std::rc::Rc;
 
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use petgraph::visit::IntoNodeReferences;
+
+use super::{read_orders, write_orders, Database};
+use crate::game::order::OrderKind;
+use crate::game::phase::Phase;
 use crate::game::region::{Region, RegionType};
-use crate::game::unit::Unit;
+use crate::game::unit::{Unit, UnitType};
 use crate::lang;
-use crate::{draw::Color, game::team::Team, game::State};
+use crate::{game::team::Team, game::State};
 
-use super::Database;
+mod errors;
+mod read;
 
-use eyre::Result;
-use itertools::Itertools;
-use petgraph::visit::IntoNodeReferences;
+pub use errors::{LegacyError, LegacyErrorKind};
+pub use read::parse;
+
+/// Labels of the prelude's lines, in Hungarian and English
+const LABELS: [(&str, &str); 4] = [
+    ("Név", "Name"),
+    ("Lépések", "Steps"),
+    ("Tengeri mezők körvonalának Színe", "Water Stroke Color"),
+    ("Szárazföld körvonal Szín", "Land Stroke Color"),
+];
+
+/// Unit types, in the order of their codes
+const UNIT_TYPES: [UnitType; 6] = [
+    UnitType::Tank,
+    UnitType::Ship,
+    UnitType::Plane,
+    UnitType::Supertank,
+    UnitType::Submarine,
+    UnitType::Artillery,
+];
+
+fn unit_type_code(unit_type: UnitType) -> usize {
+    UNIT_TYPES
+        .iter()
+        .position(|&code| code == unit_type)
+        .expect("every unit type has a code")
+}
 
 pub struct Legacy {
     name: String,
+    directory: PathBuf,
     turn: usize,
-    water_stroke: Color,
-    land_stroke: Color,
     content: String,
 }
 
 impl Legacy {
-    pub fn new(name: String, water_stroke: Color, land_stroke: Color) -> Legacy {
+    pub fn new(name: String) -> Legacy {
         Legacy {
             name,
+            directory: PathBuf::from("."),
             turn: 0,
-            water_stroke,
-            land_stroke,
             content: String::new(),
         }
     }
-}
 
-impl Database for Legacy {
-    fn read_from_state(&mut self, state: State) -> Result<()> {
-        let prelude = format!(
-            "{}: {}\n{}: {}\n{}: {}\n{}: {}",
-            lang!["Név", "Name"],
-            self.name,
-            lang!["Lépések", "Steps"],
-            self.turn,
-            lang!["Tengeri mezők körvonalának Színe", "Water Stroke Color"],
-            self.water_stroke,
-            lang!["Szárazföld körvonal Szín", "Land Stroke Color: "],
-            self.land_stroke,
-        );
-
-        let team_to_entry = |(i, team): (usize, &Rc<Team>)| {
+    /// The game as of the given turn, for loading
+    pub fn turn(mut self, turn: usize) -> Legacy {
+        self.turn = turn;
+        self
+    }
+
+    /// The game, with its files in the given directory, rather than the working one
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Legacy {
+        self.directory = directory.into();
+        self
+    }
+
+    /// The file of the game's current turn
+    pub fn filename(&self) -> String {
+        format!("{}_{}.hmap", self.name, self.turn)
+    }
+
+    fn orders_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.orders.json", self.name))
+    }
+
+    /// The games with files in the directory, with the turns stored of each
+    fn games(&self) -> Result<Vec<(String, usize)>> {
+        let mut games = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            let game = name
+                .strip_suffix(".hmap")
+                .and_then(|name| name.rsplit_once('_'))
+                .and_then(|(game, turn)| Some((game.to_owned(), turn.parse().ok()?)));
+            games.extend(game);
+        }
+        Ok(games)
+    }
+
+    /// Writes the state into the file's content. The files have no place for phases and
+    /// dislodged units, so only states between turns, in spring moves, can be written.
+    pub fn read_from_state(&mut self, state: &State) -> Result<()> {
+        if state.phase != Phase::SpringMoves {
+            return Err(anyhow!(
+                "Legacy files only store games in Spring Moves, not in {}",
+                state.phase
+            ));
+        }
+        if !state.dislodged().is_empty() {
+            return Err(anyhow!("Legacy files cannot store dislodged units"));
+        }
+        self.turn = state.turn;
+        let values = [
+            self.name.clone(),
+            self.turn.to_string(),
+            state.water_stroke.to_string(),
+            state.land_stroke.to_string(),
+        ];
+        let prelude = LABELS
+            .iter()
+            .zip(values)
+            .map(|((hungarian, english), value)| format!("{}: {value}", lang![hungarian, english]))
+            .join("\n");
+
+        // Teams are numbered in the order they are written in, by name
+        let teams: Vec<&Rc<Team>> = state.teams().iter().sorted_by_key(|t| t.name()).collect();
+
+        let team_to_entry = |(i, team): (usize, &&Rc<Team>)| {
             let bases = state
                 .regions()
                 .node_references()
-                .filter(|(_, region)| (region.owner() == Some(Rc::clone(team))))
+                .filter(|(_, region)| region.owner() == Some(Rc::clone(team)))
                 .map(|(i, region)| {
                     let tf = if region.color() == team.color() {
                         "True"
@@ -65,7 +162,23 @@ impl Database for Legacy {
             format!("{i},\"{}\",\"{}\",[{}]", team.name(), team.color(), bases)
         };
 
-        let unit_to_entry = |(_i, _unit): (usize, &Unit)| -> String { todo!() };
+        let unit_to_entry = |(i, unit): (usize, &Unit)| -> Result<String> {
+            let team = teams
+                .iter()
+                .position(|team| team.name() == unit.owner().name())
+                .ok_or(anyhow!(
+                    "Unit {i} belongs to {}, which is not in the game",
+                    unit.owner().name()
+                ))?;
+            let region = state.region_index(unit.region().name()).ok_or(anyhow!(
+                "Unit {i} is in {}, which is not on the map",
+                unit.region().name()
+            ))?;
+            Ok(format!(
+                "{i},{},{team},{region}",
+                unit_type_code(unit.unit_type())
+            ))
+        };
 
         let region_to_entry = |(i, region): (u32, &Rc<Region>)| {
             use RegionType::*;
@@ -78,30 +191,24 @@ impl Database for Legacy {
                 tp,
                 region.name(),
                 region.color(),
-                region.shape().points().first().unwrap(),
+                region.pole(),
                 region.shape()
             )
         };
 
-        // TODO add units
+        let units: Vec<String> = state
+            .units()
+            .iter()
+            .enumerate()
+            .map(unit_to_entry)
+            .collect::<Result<_>>()?;
 
         self.content = prelude
             + "\n---\n"
-            + &state
-                .teams()
-                .iter()
-                .sorted_by_key(|t| t.name())
-                .enumerate()
-                .map(team_to_entry)
-                .join("\n")
+            + &teams.iter().enumerate().map(team_to_entry).join("\n")
+            + "\n---\n"
+            + &units.join("\n")
             + "\n---\n"
-            + &state
-                .units()
-                .iter()
-                .enumerate()
-                .map(unit_to_entry)
-                .join("\n")
-            + "---\n"
             + &state
                 .regions()
                 .node_references()
@@ -111,18 +218,57 @@ impl Database for Legacy {
         Ok(())
     }
 
-    fn to_state(&self) -> Result<State> {
-        todo!()
+    /// The state of the game, as read from the file last loaded
+    pub fn to_state(&self) -> Result<State> {
+        Ok(parse(&self.content)?)
     }
 
-    fn write(&self) -> Result<()> {
-        let filename = format!("{}_{}.hmap", self.name, self.turn);
+    pub fn write(&self) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.directory.join(self.filename()), &self.content)?;
+        Ok(())
+    }
 
-        fs::write(filename, &self.content)?;
+    /// Loads the file of the game's turn
+    pub fn load(&mut self) -> Result<()> {
+        self.content = fs::read_to_string(self.directory.join(self.filename()))?;
         Ok(())
     }
+}
+
+impl Database for Legacy {
+    /// The state as of the latest turn stored. As the files do not store phases, games are read
+    /// as if between turns.
+    async fn load_state(&self) -> Result<State> {
+        let turn = self
+            .games()?
+            .into_iter()
+            .filter(|(game, _)| *game == self.name)
+            .map(|(_, turn)| turn)
+            .max()
+            .ok_or(anyhow!("No turns of {} are stored", self.name))?;
+        let filename = format!("{}_{turn}.hmap", self.name);
+        Ok(parse(&fs::read_to_string(self.directory.join(filename))?)?)
+    }
+
+    /// Writes the file of the state's turn, keeping those of earlier turns. Fails unless the
+    /// state is between turns, in spring moves, without dislodged units.
+    async fn save_state(&mut self, state: &State) -> Result<()> {
+        self.read_from_state(state)?;
+        self.write()
+    }
+
+    async fn load_orders(&self) -> Result<Vec<OrderKind>> {
+        read_orders(&self.orders_path())
+    }
+
+    async fn save_orders(&mut self, orders: &[OrderKind]) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        write_orders(&self.orders_path(), orders)
+    }
 
-    fn load(&self) -> Result<()> {
-        todo!()
+    async fn list_games(&self) -> Result<Vec<String>> {
+        let games: BTreeSet<String> = self.games()?.into_iter().map(|(game, _)| game).collect();
+        Ok(games.into_iter().collect())
     }
 }
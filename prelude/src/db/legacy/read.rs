@@ -0,0 +1,273 @@
+//! Reading .hmap files
+
+use std::{cell::RefCell, rc::Rc};
+
+use petgraph::{csr::Csr, Undirected};
+
+use super::errors::{LegacyError, LegacyErrorKind};
+use super::{LABELS, UNIT_TYPES};
+use crate::{
+    draw::{Color, Point, Shape},
+    game::{
+        phase::Phase,
+        region::{Base, Border, Region, RegionType},
+        team::Team,
+        unit::{Unit, UnitType},
+        State,
+    },
+};
+
+const TEAM: &str = "a team: index,\"name\",\"#color\",[(region,True|False),...]";
+const UNIT: &str = "a unit: index,type,team,region";
+const REGION: &str = "a region: index,type,\"name\",\"#color\",(x,y),\"M x,y ... Z\"";
+
+/// Lines of a section, numbered from 1
+type Section<'a> = Vec<(usize, &'a str)>;
+
+/// Reads the state of a game from the contents of a .hmap file
+pub fn parse(content: &str) -> Result<State, LegacyError> {
+    use LegacyErrorKind::*;
+
+    // Sections, and the line each one ends on (the separator, or the end of the file)
+    let mut sections: Vec<(Section, usize)> = Vec::new();
+    let mut section = Vec::new();
+    let mut last = 0;
+    for (i, line) in content.lines().enumerate() {
+        let (number, line) = (i + 1, line.trim_end());
+        last = number;
+        if line == "---" {
+            if sections.len() == 3 {
+                return Err(LegacyError::new(number, Unknown(line.into())));
+            }
+            sections.push((std::mem::take(&mut section), number));
+        } else if !line.is_empty() {
+            section.push((number, line));
+        }
+    }
+    sections.push((section, last + 1));
+    let mut sections = sections.into_iter();
+    let mut next = |name| {
+        sections
+            .next()
+            .ok_or(LegacyError::new(last + 1, Missing(name)))
+    };
+    let (prelude, prelude_end) = next("the prelude")?;
+    let (teams, _) = next("the teams section")?;
+    let (units, _) = next("the units section")?;
+    let (regions, _) = next("the regions section")?;
+
+    // Prelude
+    let mut values = Vec::new();
+    for (i, &(hungarian, english)) in LABELS.iter().enumerate() {
+        let (number, line) = *prelude
+            .get(i)
+            .ok_or(LegacyError::new(prelude_end, Missing(english)))?;
+        match line.split_once(": ") {
+            Some((label, value)) if label == hungarian || label == english => values.push(value),
+            _ => return Err(LegacyError::new(number, Unknown(line.into()))),
+        }
+    }
+    if let Some((number, line)) = prelude.get(LABELS.len()) {
+        return Err(LegacyError::new(*number, Unknown(line.to_string())));
+    }
+    let turn = match values[1].parse() {
+        Ok(turn) => turn,
+        Err(_) => {
+            return Err(LegacyError::new(
+                prelude[1].0,
+                Malformed("a number of steps"),
+            ))
+        }
+    };
+    let mut strokes = [2, 3].into_iter().map(|i| {
+        let (number, _) = prelude[i];
+        values[i]
+            .parse::<Color>()
+            .map_err(|_| LegacyError::new(number, Malformed("a color, like #a0b1c2")))
+    });
+    let water_stroke = strokes.next().expect("there are two strokes")?;
+    let land_stroke = strokes.next().expect("there are two strokes")?;
+
+    // Teams, whose bases are given once the regions are read
+    let mut team_list = Vec::new();
+    let mut bases = Vec::new();
+    for (i, (number, line)) in teams.into_iter().enumerate() {
+        let (team, team_bases) = team(i, line).map_err(|kind| LegacyError::new(number, kind))?;
+        team_list.push(Rc::new(team));
+        bases.push((number, team_bases));
+    }
+
+    // Regions
+    let mut map: Csr<Rc<Region>, Border, Undirected> = Csr::new();
+    for (i, (number, line)) in regions.into_iter().enumerate() {
+        let region = region(i, line).map_err(|kind| LegacyError::new(number, kind))?;
+        map.add_node(Rc::new(region));
+    }
+    let region_at = |number: usize, index: usize| {
+        (index < map.node_count())
+            .then(|| Rc::clone(&map[index as u32]))
+            .ok_or(LegacyError::new(number, NoRegion(index)))
+    };
+    for ((number, team_bases), team) in bases.into_iter().zip(team_list.iter()) {
+        for index in team_bases {
+            let region = region_at(number, index)?;
+            if !region.capture(Rc::clone(team)) {
+                return Err(LegacyError::new(number, NoBase(region.name().into())));
+            }
+        }
+    }
+
+    // Units
+    let mut unit_list = Vec::new();
+    for (i, (number, line)) in units.into_iter().enumerate() {
+        let (unit_type, team, region) =
+            unit(i, line).map_err(|kind| LegacyError::new(number, kind))?;
+        let team = team_list
+            .get(team)
+            .ok_or(LegacyError::new(number, NoTeam(team)))?;
+        let region = region_at(number, region)?;
+        unit_list.push(Unit::new(unit_type, region, Rc::clone(team)));
+    }
+
+    let mut state = State::new(team_list, map, water_stroke, land_stroke);
+    state.turn = turn;
+    // Games were saved between turns, and start with builds
+    if turn > 0 {
+        state.phase = Phase::SpringMoves;
+    }
+    state.units_mut().extend(unit_list);
+    Ok(state)
+}
+
+fn unit_type_from_code(code: usize) -> Option<UnitType> {
+    UNIT_TYPES.get(code).copied()
+}
+
+/// A team, and the indices of the regions of its bases
+fn team(i: usize, line: &str) -> Result<(Team, Vec<usize>), LegacyErrorKind> {
+    let malformed = LegacyErrorKind::Malformed(TEAM);
+    let [index, name, color, bases] = fields(line)[..] else {
+        return Err(malformed);
+    };
+    check_index(i, index, TEAM)?;
+    let name = quoted(name).ok_or(malformed.clone())?;
+    let color = quoted(color).and_then(|color| color.parse().ok());
+    let color = color.ok_or(malformed.clone())?;
+    let bases = bases
+        .strip_prefix('[')
+        .and_then(|bases| bases.strip_suffix(']'))
+        .ok_or(malformed.clone())?;
+    let bases = match bases.is_empty() {
+        true => Vec::new(),
+        false => fields(bases)
+            .into_iter()
+            .map(|base| {
+                let base = base.strip_prefix('(')?.strip_suffix(')')?;
+                match base.split_once(',')? {
+                    (region, "True" | "False") => region.parse().ok(),
+                    _ => None,
+                }
+            })
+            .collect::<Option<_>>()
+            .ok_or(malformed)?,
+    };
+    Ok((Team::new(name.into(), color), bases))
+}
+
+/// A unit's type, and the indices of its team and region
+fn unit(i: usize, line: &str) -> Result<(UnitType, usize, usize), LegacyErrorKind> {
+    let malformed = LegacyErrorKind::Malformed(UNIT);
+    let [index, unit_type, team, region] = fields(line)[..] else {
+        return Err(malformed);
+    };
+    check_index(i, index, UNIT)?;
+    let unit_type = unit_type.parse().ok().and_then(unit_type_from_code);
+    let unit_type = unit_type.ok_or(malformed.clone())?;
+    let team = team.parse().map_err(|_| malformed.clone())?;
+    let region = region.parse().map_err(|_| malformed)?;
+    Ok((unit_type, team, region))
+}
+
+/// A region, with an unowned base if it has one
+fn region(i: usize, line: &str) -> Result<Region, LegacyErrorKind> {
+    let malformed = LegacyErrorKind::Malformed(REGION);
+    let [index, region_type, name, color, pole, shape] = fields(line)[..] else {
+        return Err(malformed);
+    };
+    check_index(i, index, REGION)?;
+    // Legacy maps do not tell shores from land
+    let (region_type, base) = match region_type {
+        "0" => (RegionType::Sea, None),
+        "1" => (RegionType::Land, None),
+        "2" => (RegionType::Land, Some(RefCell::new(Base::new()))),
+        _ => return Err(malformed),
+    };
+    let name = quoted(name).ok_or(malformed.clone())?;
+    let color = quoted(color).and_then(|color| color.parse().ok());
+    let color = color.ok_or(malformed.clone())?;
+    let pole = point(
+        pole.strip_prefix('(')
+            .and_then(|pole| pole.strip_suffix(')')),
+    );
+    let pole = pole.ok_or(malformed.clone())?;
+    let shape = quoted(shape)
+        .and_then(self::shape)
+        .ok_or(malformed.clone())?;
+    Region::new(name.into(), region_type, base, shape, pole, color).map_err(|_| malformed)
+}
+
+/// Checks that an entry is numbered by its place in its section
+fn check_index(i: usize, index: &str, expected: &'static str) -> Result<(), LegacyErrorKind> {
+    match index.parse() {
+        Ok(index) if index == i => Ok(()),
+        Ok(index) => Err(LegacyErrorKind::Index {
+            expected: i,
+            found: index,
+        }),
+        Err(_) => Err(LegacyErrorKind::Malformed(expected)),
+    }
+}
+
+/// Splits a line at the commas outside of quotes, parentheses, and brackets
+fn fields(line: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let (mut depth, mut quoted, mut start) = (0, false, 0);
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' | '[' if !quoted => depth += 1,
+            ')' | ']' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                fields.push(&line[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    fields.push(&line[start..]);
+    fields
+}
+
+fn quoted(field: &str) -> Option<&str> {
+    field.strip_prefix('"')?.strip_suffix('"')
+}
+
+/// A point, as x,y
+fn point(point: Option<&str>) -> Option<Point> {
+    let (x, y) = point?.split_once(',')?;
+    Some(Point::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+/// A shape, as M x,y M x,y ... Z
+fn shape(shape: &str) -> Option<Shape> {
+    let mut words = shape.split_whitespace();
+    let mut points = Vec::new();
+    loop {
+        match words.next()? {
+            "M" => points.push(point(words.next())?),
+            "Z" => break,
+            _ => return None,
+        }
+    }
+    words.next().is_none().then(|| Shape::new(&points))
+}
@@ -2,9 +2,11 @@
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
+use std::{fs, io};
 
-use ::surrealdb::engine::remote::ws::Ws;
+use ::surrealdb::engine::any::{self, Any};
 use ::surrealdb::opt::auth::Namespace;
 use ::surrealdb::Surreal;
 use anyhow::anyhow;
@@ -14,71 +16,152 @@ use petgraph::visit::{IntoNeighbors, IntoNodeReferences};
 use petgraph::Undirected;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use surrealdb::Response;
 
 use crate::draw::Point;
 use crate::{
     draw::{Color, Shape},
     game::{
+        order::OrderKind,
+        phase::Phase,
         region::{Base, Border, Region, RegionType},
+        report::Report,
         team::Team,
+        unit::{Dislodged, Unit, UnitType},
+        victory::{EndConditions, Outcome},
         State,
     },
 };
 
 // pub mod read;
+mod history;
+mod json;
+pub mod legacy;
+
+pub use history::{HistoryEntry, Snapshot};
+pub use json::JsonFile;
+
+/// Storage of a game, wherever it is kept: its state, and the orders given for its current phase
+///
+/// Implemented by [`Surrealdb`], [`JsonFile`], and [`legacy::Legacy`], so that tools can work
+/// with any of them.
+// States share their teams and regions via `Rc`s, so they are never sent between threads, and
+// neither are the futures which hold them
+#[allow(async_fn_in_trait)]
+pub trait Database {
+    /// The game's current state
+    async fn load_state(&self) -> Result<State>;
+
+    /// Stores the state as the game's current one
+    async fn save_state(&mut self, state: &State) -> Result<()>;
+
+    /// The orders given for the game's current phase, which are none until some are saved
+    async fn load_orders(&self) -> Result<Vec<OrderKind>>;
+
+    /// Stores the orders given for the game's current phase, replacing those stored before
+    async fn save_orders(&mut self, orders: &[OrderKind]) -> Result<()>;
+
+    /// The names of the games stored alongside this one, in alphabetical order
+    async fn list_games(&self) -> Result<Vec<String>>;
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Prelude {
     pub turn: usize,
+    /// Games stored before there were phases are read as if they were in spring
+    #[serde(default)]
+    pub phase: Phase,
+    /// Games stored before there were end conditions end by elimination
+    #[serde(default)]
+    pub end_conditions: EndConditions,
+    /// Derived from the rest of the state, stored so that it need not be rebuilt to be shown
+    #[serde(default)]
+    pub outcome: Outcome,
     pub water_stroke: Color,
     pub land_stroke: Color,
 }
 
-pub struct Surrealdb<'a> {
-    name: String,
-    water_stroke: Color,
-    land_stroke: Color,
-    address: String, // IP adress / webside
-    credentials: Namespace<'a>,
-}
-
-impl Surrealdb<'_> {
-    pub fn new<'a>(
-        name: String,
-        ip_address: &'a str,
-        username: &'a str,
-        password: &'a str,
-        water_stroke: Color,
-        land_stroke: Color,
-    ) -> Surrealdb<'a> {
-        Surrealdb {
-            name: name.to_owned(),
-            water_stroke,
-            land_stroke,
-            credentials: Namespace {
-                namespace: "hadijatek",
-                username,
-                password,
-            },
-            address: ip_address.to_owned(),
+impl Prelude {
+    fn new(state: &State) -> Self {
+        Prelude {
+            turn: state.turn,
+            phase: state.phase,
+            end_conditions: state.end_conditions,
+            outcome: state.standings().outcome,
+            water_stroke: state.water_stroke,
+            land_stroke: state.land_stroke,
         }
     }
+}
 
-    pub async fn read(&self) -> anyhow::Result<crate::game::State> {
-        let db = Surreal::new::<Ws>(self.address.as_str()).await?;
-        // Namespace apparently implements clone, but .clone() is not found...
+/// Orders given for a phase, in a record of their own
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedOrders {
+    orders: Vec<OrderKind>,
+}
+
+/// Connects to SurrealDB, with the engine given by the scheme of the endpoint:
+///
+/// - `ws://127.0.0.1:8080` (or just `127.0.0.1:8080`), `wss://`, `http://`, or `https://` for a
+///   database server, signing in to the namespace with the username and password
+/// - `mem://` for an in-memory database, which is lost when the connection is dropped
+/// - `file://path/to/directory` for a database stored locally, if the `file` feature is enabled
+///
+/// Local databases are not signed in to, so the username and password are ignored. The
+/// connection uses the namespace from then on.
+pub async fn connect(
+    endpoint: &str,
+    namespace: &str,
+    username: &str,
+    password: &str,
+) -> Result<Surreal<Any>> {
+    let endpoint = match endpoint.contains("://") {
+        true => endpoint.to_owned(),
+        false => format!("ws://{endpoint}"),
+    };
+    let remote = ["ws://", "wss://", "http://", "https://"]
+        .iter()
+        .any(|scheme| endpoint.starts_with(scheme));
+
+    let db = any::connect(endpoint).await?;
+    if remote {
         db.signin(Namespace {
-            namespace: self.credentials.namespace,
-            username: self.credentials.username,
-            password: self.credentials.password,
+            namespace,
+            username,
+            password,
         })
         .await?;
+    }
+    db.use_ns(namespace).await?;
+    Ok(db)
+}
+
+/// A game stored in SurrealDB, in its own database of the connection's namespace
+pub struct Surrealdb {
+    name: String,
+    db: Surreal<Any>,
+}
+
+impl Surrealdb {
+    /// The game called `name`, on a connection made by [`connect`]
+    pub fn new(name: String, db: Surreal<Any>) -> Self {
+        Surrealdb { name, db }
+    }
+
+    /// The connection, using the game's database
+    async fn connect(&self) -> Result<Surreal<Any>> {
+        self.db.use_db(&self.name).await?;
+        Ok(self.db.clone())
+    }
 
-        db.use_ns("hadijatek").use_db(&self.name).await?;
+    /// The turn, phase, and outcome of the game, without reading the rest of its state
+    pub async fn read_prelude(&self) -> Result<Option<Prelude>> {
+        let db = self.connect().await?;
+        Ok(db.select(("prelude", "prelude")).await?)
+    }
 
-        let prelude: Option<Prelude> = db.select(("prelude", "prelude")).await?;
-        let prelude: Prelude = prelude.ok_or(anyhow!("no prelude"))?;
+    pub async fn read(&self) -> anyhow::Result<crate::game::State> {
+        let prelude: Prelude = self.read_prelude().await?.ok_or(anyhow!("no prelude"))?;
+        let db = self.connect().await?;
 
         let teams: Vec<Rc<Team>> = db.select("team").await?.into_iter().map(Rc::new).collect();
 
@@ -145,113 +228,212 @@ impl Surrealdb<'_> {
             regions.add_edge(*i, *j, border);
         }
 
-        // TODO: units
+        let units: Vec<SerializedUnit> = db.select("unit").await?;
 
-        let mut state = State::new(teams, regions, self.water_stroke, self.land_stroke);
+        let mut state = State::new(teams, regions, prelude.water_stroke, prelude.land_stroke);
 
         state.turn = prelude.turn;
+        state.phase = prelude.phase;
+        state.end_conditions = prelude.end_conditions;
+        add_units(&mut state, units)?;
 
         Ok(state)
     }
 
-    pub async fn write(&mut self, state: crate::game::State) -> Result<()> {
-        let db = Surreal::new::<Ws>(self.address.as_str()).await?;
-        // Namespace apparently implements clone, but .clone() is not found...
-        db.signin(Namespace {
-            namespace: self.credentials.namespace,
-            username: self.credentials.username,
-            password: self.credentials.password,
-        })
-        .await?;
-        // let db = Surreal::new::<Ws>("127.0.0.0:8000").await?;
-        //db.signin(Root {
-        //    username: "root",
-        //    password: "root",
-        //})
-        //.await?;
-
-        db.use_ns("hadijatek").use_db(&self.name).await?;
-
-        // Clear database
-        let _deletion: Response = db
-            .query(
-                r#"DELETE prelude RETURN NONE;
-                DELETE team RETURN NONE;
-                DELETE border RETURN NONE;
-                DELETE region RETURN NONE;
-                DELETE unit RETURN NONE;"#,
-            )
-            .await?;
-
-        // Write basic info (prelude)
-        let _prelude: Option<Prelude> = db
-            .create(("prelude", "prelude"))
-            .content(Prelude {
-                turn: state.turn,
-                water_stroke: state.water_stroke,
-                land_stroke: state.land_stroke,
-            })
-            .await?;
+    /// Writes the state in a single transaction, so that it is either written in full, or not at
+    /// all. The prelude, teams, and regions are kept under fixed IDs, and updated in place.
+    /// Borders are replaced, as are units, which have no identity from one phase to the next.
+    pub async fn write(&mut self, state: &State) -> Result<()> {
+        self.write_transaction(state, None).await
+    }
 
-        // Create team records
-        for team in state.teams() {
-            let _team_creation: Vec<Team> = db.create("team").content(team).await?;
+    /// Writes the state, and the history record of the phase which led to it, if there is one, in
+    /// the same transaction
+    async fn write_transaction(
+        &mut self,
+        state: &State,
+        history: Option<HistoryEntry>,
+    ) -> Result<()> {
+        let db = self.connect().await?;
+
+        let mut statements = vec!["BEGIN TRANSACTION;".to_owned()];
+        let mut bindings: Vec<(String, Value)> = Vec::new();
+        let mut bind = |name: String, value: Value| {
+            bindings.push((name.clone(), value));
+            format!("${name}")
+        };
+
+        // Basic info (prelude)
+        let prelude = bind("prelude".into(), serde_json::to_value(Prelude::new(state))?);
+        statements.push(format!("UPDATE prelude:prelude CONTENT {prelude};"));
+
+        // Teams, by name. Records not under their names, written before they were, are removed
+        let mut team_names = Vec::new();
+        for (i, team) in state.teams().iter().enumerate() {
+            let name = bind(format!("team_name{i}"), team.name().into());
+            let content = bind(format!("team{i}"), serde_json::to_value(team.as_ref())?);
+            statements.push(format!(
+                "UPDATE type::thing('team', {name}) CONTENT {content};"
+            ));
+            team_names.push(team.name().to_owned());
         }
+        let team_names = bind("team_names".into(), serde_json::to_value(team_names)?);
+        statements.push(format!(
+            "DELETE team WHERE name NOTINSIDE {team_names} OR id != type::thing('team', name);"
+        ));
 
-        // Create region records
+        // Regions, by name
         let map = state.regions();
-
-        let mut region_ids = HashMap::new();
+        let mut region_names = Vec::new();
         for (i, region) in map.node_references() {
-            let data: Value = serde_json::Value::Array(
-                db.create("region")
-                    .content(serialize_region(region))
-                    // .return("id") // something like this would make my life much easier
-                    .await?,
+            let name = bind(format!("region_name{i}"), region.name().into());
+            let content = bind(
+                format!("region{i}"),
+                serde_json::to_value(serialize_region(region))?,
             );
-            let id: String = data["id"]["tb"].as_str().expect("No tb field").to_owned()
-                + ":"
-                + data["id"]["id"]["String"]
-                    .as_str()
-                    .expect("No idString field");
-            region_ids.insert(i, id);
+            statements.push(format!(
+                "UPDATE type::thing('region', {name}) CONTENT {content};"
+            ));
+            region_names.push(region.name().to_owned());
         }
-
-        // Create border records
-        for (i, _region) in map.node_references() {
-            let i_id = region_ids.get(&i).expect("It was just created");
-
-            let neighbor_data = map.neighbors(i).map(|j| {
-                (
-                    map.edge_weight(i, j),
-                    region_ids.get(&j).expect("It was just created"),
-                )
-            });
-
-            for (border, j_id) in neighbor_data {
-                let query = format!("RELATE {}->border->{} CONTENT $border", i_id, j_id);
-                db.query(query)
-                    .bind(("border", serialize_border(border)))
-                    .await?;
-                // This should work but doesn't
-                /*                     let thing = db
-                .query("RELATE $i_id->border->$j_id CONTENT $border")
-                .bind(("i_id", i_id))
-                .bind(("j_id", j_id))
-                .bind(("border", serialize_border(border)))
-                .await?; */
+        let region_names = bind("region_names".into(), serde_json::to_value(region_names)?);
+        statements.push(format!(
+            "DELETE region WHERE name NOTINSIDE {region_names} \
+            OR id != type::thing('region', name);"
+        ));
+
+        // Borders, both ways. They are relations between regions, which cannot be compared with
+        // those stored without reading them outside of the transaction, so they are all replaced.
+        statements.push("DELETE border;".into());
+        let mut k = 0;
+        for (i, from) in map.node_references() {
+            for j in map.neighbors(i) {
+                let to = &map[j];
+                let border = serialize_border(map.edge_weight(i, j));
+                let from = bind(format!("from_name{k}"), from.name().into());
+                let to = bind(format!("to_name{k}"), to.name().into());
+                let border = bind(format!("border{k}"), serde_json::to_value(border)?);
+                statements.push(format!(
+                    "LET $from{k} = type::thing('region', {from}); \
+                    LET $to{k} = type::thing('region', {to}); \
+                    RELATE $from{k}->border->$to{k} CONTENT {border};"
+                ));
+                k += 1;
             }
         }
 
-        // Create unit records
-        for _unit in state.units().iter() {
-            todo!();
+        // Units, dislodged units included
+        let units = serialize_units(state);
+        statements.push("DELETE unit;".into());
+        if !units.is_empty() {
+            let units = bind("units".into(), serde_json::to_value(units)?);
+            statements.push(format!("INSERT INTO unit {units};"));
+        }
+
+        // The phase played, added to the history under the number of records before it, counted
+        // in the transaction. Creating a record which exists already fails, and with it the whole
+        // transaction
+        if let Some(entry) = history {
+            let entry = bind("history".into(), serde_json::to_value(entry)?);
+            statements.push(
+                "LET $index = (SELECT count() AS count FROM history GROUP ALL)[0].count OR 0;"
+                    .into(),
+            );
+            statements.push(format!(
+                "CREATE type::thing('history', $index) CONTENT {entry};"
+            ));
         }
 
-        Ok::<(), anyhow::Error>(())
+        statements.push("COMMIT TRANSACTION;".into());
+
+        let mut query = db.query(statements.join("\n"));
+        for binding in bindings {
+            query = query.bind(binding);
+        }
+        query.await?.check()?;
+
+        Ok(())
+    }
+
+    /// Stores the report of the last adjudicated phase, replacing the previous one
+    pub async fn write_report(&self, report: &Report) -> Result<()> {
+        let db = self.connect().await?;
+        let _report: Option<Report> = db.update(("report", "latest")).content(report).await?;
+        Ok(())
+    }
+
+    /// The report of the last adjudicated phase, if there is one yet
+    pub async fn read_report(&self) -> Result<Option<Report>> {
+        let db = self.connect().await?;
+        Ok(db.select(("report", "latest")).await?)
+    }
+}
+
+impl Database for Surrealdb {
+    async fn load_state(&self) -> Result<State> {
+        self.read().await
+    }
+
+    async fn save_state(&mut self, state: &State) -> Result<()> {
+        self.write(state).await
+    }
+
+    async fn load_orders(&self) -> Result<Vec<OrderKind>> {
+        let db = self.connect().await?;
+        let orders: Option<SavedOrders> = db.select(("orders", "current")).await?;
+        Ok(orders.map(|saved| saved.orders).unwrap_or_default())
+    }
+
+    async fn save_orders(&mut self, orders: &[OrderKind]) -> Result<()> {
+        let db = self.connect().await?;
+        let _orders: Option<SavedOrders> = db
+            .update(("orders", "current"))
+            .content(SavedOrders {
+                orders: orders.to_vec(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Games are the databases of the namespace with a prelude, as it may hold others, like that
+    /// of the web UI's users
+    async fn list_games(&self) -> Result<Vec<String>> {
+        let db = self.connect().await?;
+        let mut info = db.query("INFO FOR NS;").await?;
+        let info: Option<Value> = info.take(0)?;
+        let databases: Vec<String> = info
+            .as_ref()
+            .and_then(|info| info["db"].as_object())
+            .map(|databases| databases.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let mut games = Vec::new();
+        for name in databases {
+            db.use_db(&name).await?;
+            let prelude: Option<Value> = db.select(("prelude", "prelude")).await?;
+            if prelude.is_some() {
+                games.push(name);
+            }
+        }
+        games.sort();
+        Ok(games)
     }
 }
 
+/// Reads the orders stored in a file as JSON, which are none if there is no file
+fn read_orders(path: &Path) -> Result<Vec<OrderKind>> {
+    match fs::read_to_string(path) {
+        Ok(orders) => Ok(serde_json::from_str(&orders)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn write_orders(path: &Path, orders: &[OrderKind]) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(orders)?)?;
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct SerializedBase {
     owner_name: Option<String>,
@@ -309,6 +491,79 @@ fn deserialize_region(sregion: SerializedRegion, teams: &[Rc<Team>]) -> anyhow::
     Ok(region)
 }
 
+/// Units are stored between phases, so the regions they start the next phase on are the ones
+/// they are stored with (which is all there is to know about whether a Plane started at sea).
+/// Dislodged units are stored with the region they were driven out of, and their retreats.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedUnit {
+    pub unit_type: UnitType,
+    pub team: String,
+    pub region: String,
+    pub dislodged: Option<SerializedRetreat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedRetreat {
+    pub attacker: String,
+    pub retreats: Vec<String>,
+}
+
+fn serialize_unit(unit: &Unit, dislodged: Option<SerializedRetreat>) -> SerializedUnit {
+    SerializedUnit {
+        unit_type: unit.unit_type(),
+        team: unit.owner().name().to_owned(),
+        region: unit.region().name().to_owned(),
+        dislodged,
+    }
+}
+
+/// The units of the state, dislodged units included
+fn serialize_units(state: &State) -> Vec<SerializedUnit> {
+    let mut units: Vec<SerializedUnit> = state
+        .units()
+        .iter()
+        .map(|unit| serialize_unit(unit, None))
+        .collect();
+    for dislodged in state.dislodged().iter() {
+        let retreat = SerializedRetreat {
+            attacker: dislodged.attacker().to_owned(),
+            retreats: dislodged.retreats().to_vec(),
+        };
+        units.push(serialize_unit(dislodged.unit(), Some(retreat)));
+    }
+    units
+}
+
+/// Adds the units to the state, the dislodged ones among its dislodged units
+fn add_units(state: &mut State, units: Vec<SerializedUnit>) -> Result<()> {
+    for unit in units {
+        match deserialize_unit(unit, state)? {
+            (unit, None) => state.units_mut().push(unit),
+            (unit, Some(SerializedRetreat { attacker, retreats })) => state
+                .dislodged_mut()
+                .push(Dislodged::new(unit, attacker, retreats)),
+        }
+    }
+    Ok(())
+}
+
+fn deserialize_unit(
+    sunit: SerializedUnit,
+    state: &State,
+) -> anyhow::Result<(Unit, Option<SerializedRetreat>)> {
+    let team = state
+        .teams()
+        .iter()
+        .find(|team| team.name() == &sunit.team)
+        .ok_or(anyhow!("Team {} not found", sunit.team))?;
+    let region = state
+        .region_index(&sunit.region)
+        .ok_or(anyhow!("Region {} not found", sunit.region))?;
+    let region = &state.regions()[region];
+    let unit = Unit::new(sunit.unit_type, Rc::clone(region), Rc::clone(team));
+    Ok((unit, sunit.dislodged))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct SerializedBorder {
     border_type: String,
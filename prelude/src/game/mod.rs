@@ -3,38 +3,53 @@
 //! Contains all types needed for the implementation of game logic.
 
 use std::{
-    cell::{Ref, RefCell},
+    cell::{Ref, RefCell, RefMut},
     rc::Rc,
 };
 
 use crate::draw::Color;
 
 use self::{
+    phase::{Phase, Season},
     region::{Border, Region},
     team::Team,
-    unit::Unit,
+    unit::{Dislodged, Unit},
+    victory::{EndConditions, Outcome, Standing, Standings},
+};
+use petgraph::{
+    csr::Csr,
+    visit::{EdgeRef, IntoNodeReferences},
+    Undirected,
 };
-use petgraph::{csr::Csr, Undirected};
 
-// pub mod order;
+pub mod order;
+pub mod phase;
 pub mod region;
+pub mod report;
 pub mod team;
 pub mod unit;
+pub mod victory;
 
 /// The State struct defines the current state of the game. Most importantly, it contains the
 /// (sparse) graph of the regions on the map.
 pub struct State {
+    /// Number of movement phases played
     pub turn: usize,
+    pub phase: Phase,
+    pub end_conditions: EndConditions,
     pub water_stroke: Color,
     pub land_stroke: Color,
     teams: Vec<Rc<Team>>,
     regions: Csr<Rc<Region>, Border, Undirected>,
     units: RefCell<Vec<Unit>>,
+    /// Units driven out of their regions, waiting to retreat
+    dislodged: RefCell<Vec<Dislodged>>,
     // orders?
 }
 
 impl State {
-    /// All game states at creation will have no units
+    /// All game states at creation will have no units, and start with builds, so that teams may
+    /// summon their first units
     pub fn new(
         teams: Vec<Rc<Team>>,
         regions: Csr<Rc<Region>, Border, Undirected>,
@@ -43,14 +58,29 @@ impl State {
     ) -> Self {
         State {
             turn: 0,
+            phase: Phase::WinterBuilds,
+            end_conditions: EndConditions::default(),
             water_stroke,
             land_stroke,
             teams,
             regions,
             units: RefCell::new(Vec::new()),
+            dislodged: RefCell::new(Vec::new()),
         }
     }
 
+    pub fn is_fall(&self) -> bool {
+        self.phase.season() == Season::Fall
+    }
+
+    /// Moves on to the next phase, counting the turn if it was a movement phase
+    pub fn advance(&mut self) {
+        if self.phase.is_moves() {
+            self.turn += 1;
+        }
+        self.phase = self.phase.next();
+    }
+
     pub fn teams(&self) -> &[Rc<Team>] {
         &self.teams
     }
@@ -59,7 +89,189 @@ impl State {
         self.units.borrow()
     }
 
+    pub fn units_mut(&self) -> RefMut<Vec<Unit>> {
+        self.units.borrow_mut()
+    }
+
+    /// The unit occupying the region of the given name, if any
+    pub fn unit_in(&self, region: &str) -> Option<Unit> {
+        self.units()
+            .iter()
+            .find(|unit| unit.region().name() == region)
+            .cloned()
+    }
+
+    pub fn dislodged(&self) -> Ref<Vec<Dislodged>> {
+        self.dislodged.borrow()
+    }
+
+    pub fn dislodged_mut(&self) -> RefMut<Vec<Dislodged>> {
+        self.dislodged.borrow_mut()
+    }
+
+    /// The unit driven out of the region of the given name, if any
+    pub fn dislodged_from(&self, region: &str) -> Option<Dislodged> {
+        self.dislodged()
+            .iter()
+            .find(|dislodged| dislodged.unit().region().name() == region)
+            .cloned()
+    }
+
     pub fn regions(&self) -> &Csr<Rc<Region>, Border, Undirected> {
         &self.regions
     }
+
+    /// Index of the region of the given name in the map
+    pub fn region_index(&self, name: &str) -> Option<u32> {
+        self.regions
+            .node_references()
+            .find(|(_, region)| region.name() == name)
+            .map(|(i, _)| i)
+    }
+
+    /// Number of bases held by the team
+    pub fn bases_held(&self, team: &Team) -> usize {
+        self.regions
+            .node_references()
+            .filter(|(_, region)| region.owner().is_some_and(|owner| owner.as_ref() == team))
+            .count()
+    }
+
+    /// Whether the team holds all of its home bases
+    pub fn holds_home_bases(&self, team: &Team) -> bool {
+        self.regions
+            .node_references()
+            .filter(|(_, region)| region.is_home_base_of(team))
+            .all(|(_, region)| region.owner().is_some_and(|owner| owner.as_ref() == team))
+    }
+
+    /// The teams ordered by how well they are doing, and whether the game has ended
+    pub fn standings(&self) -> Standings {
+        let mut teams: Vec<Standing> = self
+            .teams
+            .iter()
+            .map(|team| Standing {
+                team: team.name().to_owned(),
+                bases: self.bases_held(team),
+                units: self
+                    .units()
+                    .iter()
+                    .chain(self.dislodged().iter().map(Dislodged::unit))
+                    .filter(|unit| unit.owner() == team)
+                    .count(),
+            })
+            .collect();
+        teams.sort_by(|a, b| {
+            (b.bases, b.units)
+                .cmp(&(a.bases, a.units))
+                .then_with(|| a.team.cmp(&b.team))
+        });
+
+        let most_bases = teams.first().map_or(0, |leader| leader.bases);
+        let leaders: Vec<String> = teams
+            .iter()
+            .filter(|standing| standing.bases == most_bases)
+            .map(|standing| standing.team.clone())
+            .collect();
+        let alive: Vec<&Standing> = teams
+            .iter()
+            .filter(|standing| standing.bases > 0 || standing.units > 0)
+            .collect();
+
+        let end = self.end_conditions;
+        let outcome = if end.elimination && teams.len() > 1 && alive.len() == 1 {
+            Outcome::Won(alive[0].team.clone())
+        } else if end
+            .base_majority
+            .is_some_and(|majority| most_bases >= majority)
+            && leaders.len() == 1
+        {
+            Outcome::Won(leaders[0].clone())
+        } else if end
+            .turn_limit
+            .is_some_and(|limit| self.turn >= limit && self.phase.is_builds())
+        {
+            match leaders.len() {
+                1 => Outcome::Won(leaders[0].clone()),
+                _ => Outcome::Drawn(leaders),
+            }
+        } else {
+            Outcome::Ongoing
+        };
+
+        Standings { teams, outcome }
+    }
+
+    /// The border between the two regions, if they are neighbors
+    pub fn border(&self, from: u32, to: u32) -> Option<&Border> {
+        self.regions
+            .edges(from)
+            .find(|edge| edge.target() == to)
+            .map(|edge| edge.weight())
+    }
+
+    /// Hash of everything that changes as the game is played: the turn and phase, the teams, the
+    /// owners of the bases, and the units (including dislodged ones).
+    ///
+    /// The hash does not depend on the order teams, regions, or units are stored in, nor on the
+    /// platform or Rust version, so it may be stored, and compared with later.
+    pub fn canonical_hash(&self) -> u64 {
+        let mut teams: Vec<String> = self
+            .teams
+            .iter()
+            .map(|team| format!("team {} {}", team.name(), team.color()))
+            .collect();
+        teams.sort();
+
+        let mut bases: Vec<String> = self
+            .regions
+            .node_references()
+            .filter(|(_, region)| region.has_base())
+            .map(|(_, region)| {
+                let owner = region.owner();
+                let owner = owner.as_ref().map_or("", |owner| owner.name());
+                format!("base {} {owner}", region.name())
+            })
+            .collect();
+        bases.sort();
+
+        let unit = |unit: &Unit| {
+            format!(
+                "{} {} {:?}",
+                unit.region().name(),
+                unit.owner().name(),
+                unit.unit_type()
+            )
+        };
+        let mut units: Vec<String> = self
+            .units()
+            .iter()
+            .map(|u| format!("unit {}", unit(u)))
+            .collect();
+        units.extend(self.dislodged().iter().map(|dislodged| {
+            format!(
+                "dislodged {} {} {}",
+                unit(dislodged.unit()),
+                dislodged.attacker(),
+                dislodged.retreats().join(",")
+            )
+        }));
+        units.sort();
+
+        let lines = [format!("turn {} {:?}", self.turn, self.phase)]
+            .into_iter()
+            .chain(teams)
+            .chain(bases)
+            .chain(units);
+
+        // FNV-1a, as it is simple, and stable, unlike the standard library's hashers
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for line in lines {
+            for byte in line.bytes().chain([b'\n']) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        hash
+    }
 }
@@ -1,6 +1,63 @@
-use crate::game::unit::Unit;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use super::{step, unit_type_in, IllegalOrder, Orderable};
+use crate::game::State;
+
+/// Move the unit into the target region, dislodging any unit defending there if strong enough.
+///
+/// Units with a move range of 2 may reach regions which are not adjacent to their own, by moving
+/// through a region adjacent to both.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Attack {
-    unit: Unit,
+    unit: String,
+    target: String,
+    via: Option<String>,
+}
+
+impl Attack {
+    pub fn new(unit: String, target: String) -> Self {
+        Attack {
+            unit,
+            target,
+            via: None,
+        }
+    }
+
+    /// Attack moving through another region on the way to the target
+    pub fn through(unit: String, via: String, target: String) -> Self {
+        Attack {
+            unit,
+            target,
+            via: Some(via),
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Region traversed on the way to the target, if any
+    pub fn via(&self) -> Option<&str> {
+        self.via.as_deref()
+    }
+}
+
+impl Orderable for Attack {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        let unit_type = unit_type_in(state, &self.unit)?;
+        match &self.via {
+            None => step(state, unit_type, &self.unit, &self.target),
+            Some(via) => {
+                if unit_type.move_range() < 2 || self.unit == self.target {
+                    return Err(IllegalOrder::OutOfRange(self.target.clone()));
+                }
+                step(state, unit_type, &self.unit, via)?;
+                step(state, unit_type, via, &self.target)
+            }
+        }
+    }
 }
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::{region, unit_type_in, within, IllegalOrder, Orderable};
+use crate::game::{unit::UnitType, State};
+
+/// Shell the target region with an Artillery, as if a unit was defending it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bombard {
+    unit: String,
+    target: String,
+}
+
+impl Bombard {
+    pub fn new(unit: String, target: String) -> Self {
+        Bombard { unit, target }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
+impl Orderable for Bombard {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        if unit_type_in(state, &self.unit)? != UnitType::Artillery {
+            return Err(IllegalOrder::CannotBombard);
+        }
+        let (from, target) = (region(state, &self.unit)?, region(state, &self.target)?);
+
+        let artillery = UnitType::Artillery;
+        let (range, terrain) = (artillery.support_range(), artillery.support_terrain());
+        if from == target || !within(state, from, target, range, terrain) {
+            return Err(IllegalOrder::OutOfRange(self.target.clone()));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use super::{region, unit_type_in, within, IllegalOrder, Orderable};
+use crate::game::State;
+
+/// Lend the unit's strength to another unit holding its region
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Defend {
+    unit: String,
+    defended: String,
+}
+
+impl Defend {
+    pub fn new(unit: String, defended: String) -> Self {
+        Defend { unit, defended }
+    }
+
+    /// Region of the defended unit
+    pub fn defended(&self) -> &str {
+        &self.defended
+    }
+}
+
+impl Orderable for Defend {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        let unit_type = unit_type_in(state, &self.unit)?;
+        unit_type_in(state, &self.defended)?;
+        if self.unit == self.defended {
+            return Err(IllegalOrder::SelfSupport);
+        }
+
+        let (from, defended) = (region(state, &self.unit)?, region(state, &self.defended)?);
+        let (range, terrain) = (unit_type.support_range(), unit_type.support_terrain());
+        if !within(state, from, defended, range, terrain) {
+            return Err(IllegalOrder::OutOfRange(self.defended.clone()));
+        }
+
+        Ok(())
+    }
+}
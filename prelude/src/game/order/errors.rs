@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::{error, fmt};
+
+use crate::game::unit::UnitType;
+
+/// Reasons an order may be rejected before resolution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IllegalOrder {
+    NoUnit(String),
+    NoRegion(String),
+    NoTeam(String),
+    Occupied(String),
+    NotNeighbors(String, String),
+    Impassable(String, String),
+    Uninhabitable(String),
+    OutOfRange(String),
+    Unreachable(String),
+    SelfSupport,
+    CannotBombard,
+    CannotRetreat(String),
+    NotHomeBase(String),
+    SuperUnit,
+    NoBuildsLeft,
+    CannotTransform(UnitType, UnitType),
+    NoSuperUnitsLeft,
+    Duplicate,
+    WrongPhase,
+    GameOver,
+}
+
+impl fmt::Display for IllegalOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use IllegalOrder::*;
+        match self {
+            NoUnit(region) => write!(f, "There is no unit in {region}!"),
+            NoRegion(region) => write!(f, "There is no region called {region}!"),
+            NoTeam(team) => write!(f, "There is no team called {team}!"),
+            Occupied(region) => write!(f, "{region} is already occupied!"),
+            NotNeighbors(from, to) => write!(f, "{from} and {to} are not neighbors!"),
+            Impassable(from, to) => {
+                write!(
+                    f,
+                    "The unit cannot cross the border between {from} and {to}!"
+                )
+            }
+            Uninhabitable(region) => write!(f, "The unit cannot enter {region}!"),
+            OutOfRange(region) => write!(f, "{region} is out of the unit's range!"),
+            Unreachable(region) => write!(f, "The supported unit cannot reach {region}!"),
+            SelfSupport => write!(f, "Units cannot support themselves!"),
+            CannotBombard => write!(f, "Only Artillery may bombard!"),
+            CannotRetreat(region) => write!(f, "The unit cannot retreat to {region}!"),
+            NotHomeBase(region) => write!(f, "{region} is not one of the team's home bases!"),
+            SuperUnit => write!(f, "Super units cannot be summoned, only transformed into!"),
+            NoBuildsLeft => write!(f, "The team has no more bases than units!"),
+            CannotTransform(from, into) => write!(f, "A {from:?} cannot become a {into:?}!"),
+            NoSuperUnitsLeft => write!(f, "The team is not entitled to more super units!"),
+            Duplicate => write!(f, "The unit has already been given an order!"),
+            WrongPhase => write!(f, "This order cannot be given in this phase!"),
+            GameOver => write!(f, "The game is over!"),
+        }
+    }
+}
+
+impl error::Error for IllegalOrder {}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use super::{unit_type_in, IllegalOrder, Orderable};
+use crate::game::State;
+
+/// Disband the unit, removing it from the game. Dislodged units may be disbanded instead of
+/// retreating.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Kill {
+    unit: String,
+}
+
+impl Kill {
+    pub fn new(unit: String) -> Self {
+        Kill { unit }
+    }
+}
+
+impl Orderable for Kill {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        if state.dislodged_from(&self.unit).is_some() {
+            return Ok(());
+        }
+        unit_type_in(state, &self.unit).map(|_| ())
+    }
+}
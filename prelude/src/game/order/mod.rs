@@ -1,28 +1,232 @@
-use super::{team::Team, unit::Unit, State};
-use std::{fmt::Debug, rc::Rc};
+//! Orders are the instructions Teams give to their units each turn
+//!
+//! Orders refer to units by the name of the region they occupy, and to regions by their names, so
+//! that they can be submitted, stored, and (de)serialized independently of a State.
+//!
+//! Every unit receives exactly one order per turn, units without orders Stay.
+//!
+//! # Orders:
+//! * Stay - hold the unit's region
+//! * Attack - move into an adjacent region, or one 2 regions away through a given region
+//! * Support - add the unit's strength to another unit's Attack
+//! * Defend - add the unit's strength to another unit holding its region
+//! * Bombard - shell a region with an Artillery
+//! * Retreat - move a dislodged unit to a free neighboring region
+//! * Kill - disband the unit
+//! * Summon - place a new unit on a base
+//! * Transform - change the unit's type
+
+use super::{
+    unit::{Terrain, UnitType},
+    State,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Debug};
 
 mod attack;
 mod bombard;
 mod defend;
+mod errors;
 mod kill;
+mod retreat;
 mod stay;
 mod summon;
 mod support;
 mod transform;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum OrderStatus {
+pub use attack::Attack;
+pub use bombard::Bombard;
+pub use defend::Defend;
+pub use errors::IllegalOrder;
+pub use kill::Kill;
+pub use retreat::Retreat;
+pub use stay::Stay;
+pub use summon::Summon;
+pub use support::Support;
+pub use transform::Transform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatus {
     Failed,
     Unresolved,
     Succeeded,
 }
 
-#[derive(Debug)]
-struct Order {
+/// Why a legal order failed during resolution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Failure {
+    /// Beaten by the defender of the target, or by another attack on it
+    Bounced,
+    /// Stopped by a unit holding a region on the way to the target
+    Blocked(String),
+    /// Stopped by the bombardment of the target, or of a region on the way to it
+    Bombarded(String),
+    /// The supporting unit was attacked
+    Cut,
+    /// The supported unit was not given the supported order
+    Unmatched,
+    /// The unit was driven out of its region
+    Dislodged,
+    /// Part of a cycle of orders without a consistent outcome, failed as a whole
+    Paradox,
+}
+
+/// All the kinds of orders a unit may be given
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderKind {
+    Stay(Stay),
+    Attack(Attack),
+    Support(Support),
+    Defend(Defend),
+    Bombard(Bombard),
+    Retreat(Retreat),
+    Kill(Kill),
+    Summon(Summon),
+    Transform(Transform),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Order {
     status: OrderStatus,
-    order: Box<dyn Orderable>,
+    order: OrderKind,
+    illegal: Option<IllegalOrder>,
+    failure: Option<Failure>,
+}
+
+impl Order {
+    /// All orders start out unresolved
+    pub fn new(order: OrderKind) -> Self {
+        Order {
+            status: OrderStatus::Unresolved,
+            order,
+            illegal: None,
+            failure: None,
+        }
+    }
+
+    /// Fails the order without resolving it
+    pub fn reject(&mut self, reason: IllegalOrder) {
+        self.status = OrderStatus::Failed;
+        self.illegal = Some(reason);
+    }
+
+    /// Why the order was rejected, if it was
+    pub fn illegal(&self) -> Option<&IllegalOrder> {
+        self.illegal.as_ref()
+    }
+
+    /// Fails the order during resolution
+    pub fn fail(&mut self, failure: Failure) {
+        self.status = OrderStatus::Failed;
+        self.failure = Some(failure);
+    }
+
+    /// Why the order failed during resolution, if it did
+    pub fn failure(&self) -> Option<&Failure> {
+        self.failure.as_ref()
+    }
+
+    pub fn status(&self) -> OrderStatus {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: OrderStatus) {
+        self.status = status
+    }
+
+    pub fn order(&self) -> &OrderKind {
+        &self.order
+    }
+}
+
+pub trait Orderable: Debug {
+    /// Name of the region occupied by the ordered unit
+    fn unit(&self) -> &str;
+
+    /// Checks whether the order may be carried out at all in the given state, based on the map
+    /// and the movement rules of the ordered unit
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder>;
+}
+
+impl Orderable for OrderKind {
+    fn unit(&self) -> &str {
+        use OrderKind::*;
+        match self {
+            Stay(order) => order.unit(),
+            Attack(order) => order.unit(),
+            Support(order) => order.unit(),
+            Defend(order) => order.unit(),
+            Bombard(order) => order.unit(),
+            Retreat(order) => order.unit(),
+            Kill(order) => order.unit(),
+            Summon(order) => order.unit(),
+            Transform(order) => order.unit(),
+        }
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        use OrderKind::*;
+        match self {
+            Stay(order) => order.legal(state),
+            Attack(order) => order.legal(state),
+            Support(order) => order.legal(state),
+            Defend(order) => order.legal(state),
+            Bombard(order) => order.legal(state),
+            Retreat(order) => order.legal(state),
+            Kill(order) => order.legal(state),
+            Summon(order) => order.legal(state),
+            Transform(order) => order.legal(state),
+        }
+    }
+}
+
+/// Index of the region of the given name
+fn region(state: &State, name: &str) -> Result<u32, IllegalOrder> {
+    state
+        .region_index(name)
+        .ok_or_else(|| IllegalOrder::NoRegion(name.to_owned()))
+}
+
+/// Type of the unit in the region of the given name
+fn unit_type_in(state: &State, name: &str) -> Result<UnitType, IllegalOrder> {
+    state
+        .unit_in(name)
+        .map(|unit| unit.unit_type())
+        .ok_or_else(|| IllegalOrder::NoUnit(name.to_owned()))
+}
+
+/// Checks that a unit of the given type may move from a region into a neighboring one
+fn step(state: &State, unit_type: UnitType, from: &str, to: &str) -> Result<(), IllegalOrder> {
+    let (i, j) = (region(state, from)?, region(state, to)?);
+    let border = state
+        .border(i, j)
+        .ok_or_else(|| IllegalOrder::NotNeighbors(from.to_owned(), to.to_owned()))?;
+    let terrain = unit_type.move_terrain();
+    if !terrain.crosses(border) {
+        return Err(IllegalOrder::Impassable(from.to_owned(), to.to_owned()));
+    }
+    if !terrain.occupies(state.regions()[j].region_type()) {
+        return Err(IllegalOrder::Uninhabitable(to.to_owned()));
+    }
+    Ok(())
 }
 
-trait Orderable: Debug {
-    fn legal(&self, state: State) -> bool;
+/// Whether the target region is reachable from a region in at most `range` steps, only crossing
+/// borders of the given terrain
+fn within(state: &State, from: u32, to: u32, range: usize, terrain: Terrain) -> bool {
+    let mut reached = HashSet::from([from]);
+    let mut frontier = vec![from];
+    for _ in 0..range {
+        let mut next = Vec::new();
+        for &i in frontier.iter() {
+            for &j in state.regions().neighbors_slice(i) {
+                let crossable = state.border(i, j).is_some_and(|b| terrain.crosses(b));
+                if crossable && reached.insert(j) {
+                    next.push(j);
+                }
+            }
+        }
+        frontier = next;
+    }
+    reached.contains(&to)
 }
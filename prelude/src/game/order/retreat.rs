@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::{IllegalOrder, Orderable};
+use crate::game::State;
+
+/// Move a dislodged unit to one of the free regions next to the one it was driven out of
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Retreat {
+    unit: String,
+    target: String,
+}
+
+impl Retreat {
+    pub fn new(unit: String, target: String) -> Self {
+        Retreat { unit, target }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
+impl Orderable for Retreat {
+    /// The region the unit was driven out of
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        let dislodged = state
+            .dislodged_from(&self.unit)
+            .ok_or_else(|| IllegalOrder::NoUnit(self.unit.clone()))?;
+        if !dislodged.retreats().contains(&self.target) {
+            return Err(IllegalOrder::CannotRetreat(self.target.clone()));
+        }
+
+        Ok(())
+    }
+}
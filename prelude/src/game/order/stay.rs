@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use super::{unit_type_in, IllegalOrder, Orderable};
+use crate::game::State;
+
+/// Hold the current region
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stay {
+    unit: String,
+}
+
+impl Stay {
+    pub fn new(unit: String) -> Self {
+        Stay { unit }
+    }
+}
+
+impl Orderable for Stay {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        unit_type_in(state, &self.unit).map(|_| ())
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use super::{region, IllegalOrder, Orderable};
+use crate::game::{unit::UnitType, State};
+
+/// Place a new simple unit of the team on one of its free home bases
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Summon {
+    team: String,
+    unit_type: UnitType,
+    region: String,
+}
+
+impl Summon {
+    pub fn new(team: String, unit_type: UnitType, region: String) -> Self {
+        Summon {
+            team,
+            unit_type,
+            region,
+        }
+    }
+
+    /// Name of the summoning team
+    pub fn team(&self) -> &str {
+        &self.team
+    }
+
+    pub fn unit_type(&self) -> UnitType {
+        self.unit_type
+    }
+}
+
+impl Orderable for Summon {
+    /// The region the new unit is placed on
+    fn unit(&self) -> &str {
+        &self.region
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        if !state.teams().iter().any(|team| team.name() == &self.team) {
+            return Err(IllegalOrder::NoTeam(self.team.clone()));
+        }
+        if self.unit_type.is_super() {
+            return Err(IllegalOrder::SuperUnit);
+        }
+        let i = region(state, &self.region)?;
+        let region = &state.regions()[i];
+        let home = match region.owner() {
+            Some(owner) => owner.name() == &self.team && region.is_home_base_of(&owner),
+            None => false,
+        };
+        if !home {
+            return Err(IllegalOrder::NotHomeBase(self.region.clone()));
+        }
+        if state.unit_in(&self.region).is_some() {
+            return Err(IllegalOrder::Occupied(self.region.clone()));
+        }
+        if !self.unit_type.move_terrain().occupies(region.region_type()) {
+            return Err(IllegalOrder::Uninhabitable(self.region.clone()));
+        }
+
+        Ok(())
+    }
+}
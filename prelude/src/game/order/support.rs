@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use super::{region, unit_type_in, within, IllegalOrder, Orderable};
+use crate::game::State;
+
+/// Lend the unit's strength to another unit's attack on the target region
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Support {
+    unit: String,
+    supported: String,
+    target: String,
+}
+
+impl Support {
+    pub fn new(unit: String, supported: String, target: String) -> Self {
+        Support {
+            unit,
+            supported,
+            target,
+        }
+    }
+
+    /// Region of the supported unit
+    pub fn supported(&self) -> &str {
+        &self.supported
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
+impl Orderable for Support {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        let unit_type = unit_type_in(state, &self.unit)?;
+        let supported_type = unit_type_in(state, &self.supported)?;
+        if self.unit == self.supported || self.unit == self.target {
+            return Err(IllegalOrder::SelfSupport);
+        }
+        let (from, supported, target) = (
+            region(state, &self.unit)?,
+            region(state, &self.supported)?,
+            region(state, &self.target)?,
+        );
+
+        let (range, terrain) = (unit_type.support_range(), unit_type.support_terrain());
+        if !within(state, from, target, range, terrain) {
+            return Err(IllegalOrder::OutOfRange(self.target.clone()));
+        }
+
+        let (range, terrain) = (supported_type.move_range(), supported_type.move_terrain());
+        if !within(state, supported, target, range, terrain)
+            || !terrain.occupies(state.regions()[target].region_type())
+        {
+            return Err(IllegalOrder::Unreachable(self.target.clone()));
+        }
+
+        Ok(())
+    }
+}
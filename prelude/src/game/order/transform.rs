@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use super::{region, unit_type_in, IllegalOrder, Orderable};
+use crate::game::{unit::UnitType, State};
+
+/// Change the type of the unit, upgrading a simple unit to a super unit or vice versa
+///
+/// Tanks may become Supertanks, Artillery, or Planes, and Ships may become Submarines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transform {
+    unit: String,
+    into: UnitType,
+}
+
+impl Transform {
+    pub fn new(unit: String, into: UnitType) -> Self {
+        Transform { unit, into }
+    }
+
+    /// The type the unit becomes
+    pub fn into_type(&self) -> UnitType {
+        self.into
+    }
+}
+
+impl Orderable for Transform {
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn legal(&self, state: &State) -> Result<(), IllegalOrder> {
+        let unit_type = unit_type_in(state, &self.unit)?;
+        if !unit_type.transforms_into(self.into) {
+            return Err(IllegalOrder::CannotTransform(unit_type, self.into));
+        }
+        let i = region(state, &self.unit)?;
+        if !self
+            .into
+            .move_terrain()
+            .occupies(state.regions()[i].region_type())
+        {
+            return Err(IllegalOrder::Uninhabitable(self.unit.clone()));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+//! Games are played in years, each made up of phases
+//!
+//! In spring and in fall, units move, then the units dislodged by the moves retreat. At the end
+//! of fall, units capture the bases they stand on. In winter, teams summon new units, and
+//! transform existing ones.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Season {
+    Spring,
+    Fall,
+    Winter,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    #[default]
+    SpringMoves,
+    SpringRetreats,
+    FallMoves,
+    FallRetreats,
+    WinterBuilds,
+}
+
+impl Phase {
+    /// The phase following this one
+    pub fn next(&self) -> Phase {
+        use Phase::*;
+        match self {
+            SpringMoves => SpringRetreats,
+            SpringRetreats => FallMoves,
+            FallMoves => FallRetreats,
+            FallRetreats => WinterBuilds,
+            WinterBuilds => SpringMoves,
+        }
+    }
+
+    pub fn season(&self) -> Season {
+        use Phase::*;
+        match self {
+            SpringMoves | SpringRetreats => Season::Spring,
+            FallMoves | FallRetreats => Season::Fall,
+            WinterBuilds => Season::Winter,
+        }
+    }
+
+    /// Whether units move in this phase
+    pub fn is_moves(&self) -> bool {
+        matches!(self, Phase::SpringMoves | Phase::FallMoves)
+    }
+
+    /// Whether dislodged units retreat in this phase
+    pub fn is_retreats(&self) -> bool {
+        matches!(self, Phase::SpringRetreats | Phase::FallRetreats)
+    }
+
+    /// Whether units are summoned and transformed in this phase
+    pub fn is_builds(&self) -> bool {
+        matches!(self, Phase::WinterBuilds)
+    }
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Phase::*;
+        let action = match self {
+            SpringMoves | FallMoves => "Moves",
+            SpringRetreats | FallRetreats => "Retreats",
+            WinterBuilds => "Builds",
+        };
+        write!(f, "{} {}", self.season(), action)
+    }
+}
@@ -20,7 +20,7 @@
 //!
 //! Regions (except Sea) may contain bases, which are the sources of units for teams, the team
 //! which owns the most at the end of the game will be the victor. Bases may be unowned, or owned
-//! by a Team.
+//! by a Team. A unit ending a fall turn on a base captures it for its Team.
 
 use std::{
     cell::{Ref, RefCell},
@@ -66,6 +66,9 @@ impl Base {
     pub fn owner(&self) -> Option<Rc<Team>> {
         self.owner.as_ref().map(Rc::clone)
     }
+    pub fn clear(&mut self) {
+        self.owner = None
+    }
 }
 
 impl Default for Base {
@@ -130,6 +133,29 @@ impl Region {
             .and_then(|r_base| r_base.borrow().owner())
     }
 
+    /// Gives the region's base to the team, returning whether the region has a base at all
+    pub fn capture(&self, team: Rc<Team>) -> bool {
+        match &self.base {
+            Some(base) => {
+                base.borrow_mut().set(team);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Frees the region's base, if it has one
+    pub fn release(&self) {
+        if let Some(base) = &self.base {
+            base.borrow_mut().clear();
+        }
+    }
+
+    /// Home bases are the bases in a team's color, where it may summon new units
+    pub fn is_home_base_of(&self, team: &Team) -> bool {
+        self.has_base() && self.color == team.color()
+    }
+
     pub fn shape(&self) -> &Shape {
         &self.shape
     }
@@ -0,0 +1,70 @@
+//! Reports record what happened during the adjudication of a phase of a turn
+//!
+//! Besides the results of the orders, a turn has consequences no order asked for, such as Planes
+//! running out of fuel, bases being captured, or super units being downgraded. These are recorded
+//! as events, so players can see what happened to their units.
+//!
+//! Reports can be written out as text for the players, in any of the supported languages.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{order::Order, phase::Phase, unit::UnitType, victory::Standings};
+
+#[cfg(feature = "ssr")]
+mod text;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    /// A Plane which started and ended the turn over Sea ran out of fuel, and was removed
+    OutOfFuel { team: String, region: String },
+    /// A super unit was downgraded, as its team was no longer entitled to it
+    Downgraded {
+        team: String,
+        region: String,
+        into: UnitType,
+    },
+    /// A unit was driven out of its region by an attack, and must retreat to one of the given
+    /// regions, or be disbanded
+    Dislodged {
+        team: String,
+        region: String,
+        attacker: String,
+        retreats: Vec<String>,
+    },
+    /// A unit was removed, as it had nowhere to go
+    Disbanded { team: String, region: String },
+    /// A unit ended a fall turn on a base not held by its team, and captured it
+    Captured {
+        team: String,
+        region: String,
+        previous: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    /// The turn, and the phase of it that was adjudicated
+    pub turn: usize,
+    pub phase: Phase,
+    pub orders: Vec<Order>,
+    /// Team of the unit each order was given to, by the region the unit was on
+    pub teams: HashMap<String, String>,
+    pub events: Vec<Event>,
+    /// The standings after the phase, including whether the game is over
+    pub standings: Option<Standings>,
+}
+
+impl Report {
+    pub fn new(turn: usize, phase: Phase, orders: Vec<Order>) -> Self {
+        Report {
+            turn,
+            phase,
+            orders,
+            teams: HashMap::new(),
+            events: Vec::new(),
+            standings: None,
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! Reports written out as text, for the players
+//!
+//! Orders are listed by team, with their results, followed by the events of the phase, and the
+//! standings of the teams.
+
+use std::collections::BTreeMap;
+
+use super::{Event, Report};
+use crate::game::{
+    order::{Failure, IllegalOrder, Order, OrderKind, OrderStatus, Orderable},
+    phase::Phase,
+    unit::UnitType,
+    victory::Outcome,
+};
+use crate::lang;
+use crate::lang::Language;
+
+impl Report {
+    /// The report as text, in the given language
+    pub fn text(&self, language: Language) -> String {
+        let mut lines = vec![lang![language =>
+            format!("{}. kör, {}", self.turn, phase(self.phase, language)),
+            format!("Turn {}, {}", self.turn, phase(self.phase, language)),
+        ]];
+
+        // Orders by team, followed by those given to units which do not exist
+        let mut by_team: BTreeMap<&str, Vec<&Order>> = BTreeMap::new();
+        let mut unknown_orders = Vec::new();
+        for order in self.orders.iter() {
+            let team = match order.order() {
+                OrderKind::Summon(summon) => Some(summon.team()),
+                order => self.teams.get(order.unit()).map(String::as_str),
+            };
+            match team {
+                Some(team) => by_team.entry(team).or_default().push(order),
+                None => unknown_orders.push(order),
+            }
+        }
+        let unknown = lang![language => "Ismeretlen egységek:", "Unknown units:"];
+        let groups = by_team
+            .into_iter()
+            .map(|(team, orders)| (format!("{team}:"), orders))
+            .chain((!unknown_orders.is_empty()).then_some((unknown, unknown_orders)));
+        for (heading, orders) in groups {
+            lines.push(String::new());
+            lines.push(heading);
+            for order in orders {
+                lines.push(format!(
+                    "  {}: {}",
+                    order_kind(order.order(), language),
+                    status(order, language)
+                ));
+            }
+        }
+
+        if !self.events.is_empty() {
+            lines.push(String::new());
+            lines.push(lang![language => "Események:", "Events:"]);
+            for event in self.events.iter() {
+                lines.push(format!("  {}", self::event(event, language)));
+            }
+        }
+
+        if let Some(standings) = &self.standings {
+            lines.push(String::new());
+            lines.push(lang![language => "Állás:", "Standings:"]);
+            for standing in standings.teams.iter() {
+                let (team, bases, units) = (&standing.team, standing.bases, standing.units);
+                lines.push(lang![language =>
+                    format!("  {team}: {bases} bázis, {units} egység"),
+                    format!("  {team}: {bases} bases, {units} units"),
+                ]);
+            }
+            match &standings.outcome {
+                Outcome::Ongoing => (),
+                Outcome::Won(team) => lines.push(lang![language =>
+                    format!("Vége a játéknak, {team} nyert!"),
+                    format!("Game over, {team} won!"),
+                ]),
+                Outcome::Drawn(teams) => {
+                    let teams = teams.join(", ");
+                    lines.push(lang![language =>
+                        format!("Vége a játéknak, döntetlen: {teams}!"),
+                        format!("Game over, drawn between {teams}!"),
+                    ])
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+fn phase(phase: Phase, language: Language) -> String {
+    use Phase::*;
+    match phase {
+        SpringMoves => lang![language => "tavaszi lépések", "spring moves"],
+        SpringRetreats => lang![language => "tavaszi visszavonulások", "spring retreats"],
+        FallMoves => lang![language => "őszi lépések", "fall moves"],
+        FallRetreats => lang![language => "őszi visszavonulások", "fall retreats"],
+        WinterBuilds => lang![language => "téli toborzás", "winter builds"],
+    }
+}
+
+fn unit_type(unit_type: UnitType, language: Language) -> String {
+    use UnitType::*;
+    match unit_type {
+        Tank => lang![language => "tank", "Tank"],
+        Ship => lang![language => "hajó", "Ship"],
+        Plane => lang![language => "repülő", "Plane"],
+        Supertank => lang![language => "szupertank", "Supertank"],
+        Submarine => lang![language => "tengeralattjáró", "Submarine"],
+        Artillery => lang![language => "tüzérség", "Artillery"],
+    }
+}
+
+fn order_kind(order: &OrderKind, language: Language) -> String {
+    let unit = order.unit();
+    match order {
+        OrderKind::Stay(_) => lang![language => format!("{unit} marad"), format!("{unit} stays")],
+        OrderKind::Attack(attack) => {
+            let target = attack.target();
+            match attack.via() {
+                None => lang![language =>
+                    format!("{unit} támad: {target}"),
+                    format!("{unit} attacks {target}"),
+                ],
+                Some(via) => lang![language =>
+                    format!("{unit} támad: {target}, {via} érintésével"),
+                    format!("{unit} attacks {target} via {via}"),
+                ],
+            }
+        }
+        OrderKind::Support(support) => {
+            let (supported, target) = (support.supported(), support.target());
+            lang![language =>
+                format!("{unit} támogatja: {supported} támadását {target} ellen"),
+                format!("{unit} supports {supported} into {target}"),
+            ]
+        }
+        OrderKind::Defend(defend) => {
+            let defended = defend.defended();
+            lang![language =>
+                format!("{unit} védi: {defended}"),
+                format!("{unit} defends {defended}"),
+            ]
+        }
+        OrderKind::Bombard(bombard) => {
+            let target = bombard.target();
+            lang![language =>
+                format!("{unit} bombázza: {target}"),
+                format!("{unit} bombards {target}"),
+            ]
+        }
+        OrderKind::Retreat(retreat) => {
+            let target = retreat.target();
+            lang![language =>
+                format!("{unit} visszavonul: {target}"),
+                format!("{unit} retreats to {target}"),
+            ]
+        }
+        OrderKind::Kill(_) => {
+            lang![language => format!("{unit} feloszlik"), format!("{unit} disbands")]
+        }
+        OrderKind::Summon(summon) => {
+            let summoned = unit_type(summon.unit_type(), language);
+            lang![language =>
+                format!("{summoned} toborzása: {unit}"),
+                format!("summon a {summoned} in {unit}"),
+            ]
+        }
+        OrderKind::Transform(transform) => {
+            let into = unit_type(transform.into_type(), language);
+            lang![language =>
+                format!("{unit} átalakul: {into}"),
+                format!("{unit} transforms into a {into}"),
+            ]
+        }
+    }
+}
+
+fn status(order: &Order, language: Language) -> String {
+    if let Some(reason) = order.illegal() {
+        return lang![language =>
+            format!("szabálytalan, {}", illegal(reason, language)),
+            format!("illegal, {}", illegal(reason, language)),
+        ];
+    }
+    match (order.status(), order.failure()) {
+        (OrderStatus::Succeeded, _) => lang![language => "sikeres", "succeeded"],
+        (OrderStatus::Unresolved, _) => lang![language => "eldöntetlen", "unresolved"],
+        (OrderStatus::Failed, None) => lang![language => "sikertelen", "failed"],
+        (OrderStatus::Failed, Some(failure)) => lang![language =>
+            format!("sikertelen, {}", self::failure(failure, language)),
+            format!("failed, {}", self::failure(failure, language)),
+        ],
+    }
+}
+
+fn failure(failure: &Failure, language: Language) -> String {
+    use Failure::*;
+    match failure {
+        Bounced => lang![language => "visszapattant", "bounced"],
+        Blocked(region) => lang![language =>
+            format!("{region} elzárta az utat"),
+            format!("blocked in {region}"),
+        ],
+        Bombarded(region) => lang![language =>
+            format!("{region} bombázás alatt áll"),
+            format!("{region} is bombarded"),
+        ],
+        Cut => lang![language => "megszakították", "cut"],
+        Unmatched => lang![language =>
+            "a támogatott egység mást csinált",
+            "the supported unit was given another order",
+        ],
+        Dislodged => lang![language => "kiszorították", "dislodged"],
+        Paradox => lang![language => "paradoxon része", "part of a paradox"],
+    }
+}
+
+fn illegal(reason: &IllegalOrder, language: Language) -> String {
+    use IllegalOrder::*;
+    match reason {
+        NoUnit(region) => lang![language =>
+            format!("nincs egység itt: {region}"),
+            format!("there is no unit in {region}"),
+        ],
+        NoRegion(region) => lang![language =>
+            format!("nincs {region} nevű mező"),
+            format!("there is no region called {region}"),
+        ],
+        NoTeam(team) => lang![language =>
+            format!("nincs {team} nevű csapat"),
+            format!("there is no team called {team}"),
+        ],
+        Occupied(region) => lang![language =>
+            format!("{region} már foglalt"),
+            format!("{region} is already occupied"),
+        ],
+        NotNeighbors(from, to) => lang![language =>
+            format!("{from} és {to} nem szomszédosak"),
+            format!("{from} and {to} are not neighbors"),
+        ],
+        Impassable(from, to) => lang![language =>
+            format!("az egység nem léphet át {from} és {to} határán"),
+            format!("the unit cannot cross the border between {from} and {to}"),
+        ],
+        Uninhabitable(region) => lang![language =>
+            format!("az egység nem léphet be ide: {region}"),
+            format!("the unit cannot enter {region}"),
+        ],
+        OutOfRange(region) => lang![language =>
+            format!("{region} az egység hatótávolságán kívül esik"),
+            format!("{region} is out of the unit's range"),
+        ],
+        Unreachable(region) => lang![language =>
+            format!("a támogatott egység nem érheti el: {region}"),
+            format!("the supported unit cannot reach {region}"),
+        ],
+        SelfSupport => lang![language =>
+            "egység nem támogathatja önmagát",
+            "units cannot support themselves",
+        ],
+        CannotBombard => lang![language =>
+            "csak tüzérség bombázhat",
+            "only Artillery may bombard",
+        ],
+        CannotRetreat(region) => lang![language =>
+            format!("az egység nem vonulhat vissza ide: {region}"),
+            format!("the unit cannot retreat to {region}"),
+        ],
+        NotHomeBase(region) => lang![language =>
+            format!("{region} nem a csapat egyik otthoni bázisa"),
+            format!("{region} is not one of the team's home bases"),
+        ],
+        SuperUnit => lang![language =>
+            "szuper egységet nem lehet toborozni, csak átalakítani",
+            "super units cannot be summoned, only transformed into",
+        ],
+        NoBuildsLeft => lang![language =>
+            "a csapatnak nincs több bázisa, mint egysége",
+            "the team has no more bases than units",
+        ],
+        CannotTransform(from, into) => {
+            let (from, into) = (unit_type(*from, language), unit_type(*into, language));
+            lang![language =>
+                format!("{from} nem alakulhat át erre: {into}"),
+                format!("a {from} cannot become a {into}"),
+            ]
+        }
+        NoSuperUnitsLeft => lang![language =>
+            "a csapatnak nem jár több szuper egység",
+            "the team is not entitled to more super units",
+        ],
+        Duplicate => lang![language =>
+            "az egység már kapott parancsot",
+            "the unit has already been given an order",
+        ],
+        WrongPhase => lang![language =>
+            "ez a parancs nem adható ki ebben a szakaszban",
+            "this order cannot be given in this phase",
+        ],
+        GameOver => lang![language => "vége a játéknak", "the game is over"],
+    }
+}
+
+fn event(event: &Event, language: Language) -> String {
+    match event {
+        Event::OutOfFuel { team, region } => lang![language =>
+            format!("{team} repülője kifogyott az üzemanyagból itt: {region}"),
+            format!("{team}'s Plane ran out of fuel in {region}"),
+        ],
+        Event::Downgraded { team, region, into } => {
+            let into = unit_type(*into, language);
+            lang![language =>
+                format!("{team} egysége itt: {region} visszaalakult: {into}"),
+                format!("{team}'s unit in {region} was downgraded into a {into}"),
+            ]
+        }
+        Event::Dislodged {
+            team,
+            region,
+            attacker,
+            retreats,
+        } => {
+            let retreats = match retreats.is_empty() {
+                true => lang![language => "sehova", "nowhere"],
+                false => retreats.join(", "),
+            };
+            lang![language =>
+                format!(
+                    "{team} egységét kiszorították innen: {region} ({attacker} felől), \
+                    visszavonulhat ide: {retreats}"
+                ),
+                format!(
+                    "{team}'s unit was dislodged from {region} (from {attacker}), and may \
+                    retreat to {retreats}"
+                ),
+            ]
+        }
+        Event::Disbanded { team, region } => lang![language =>
+            format!("{team} egysége itt: {region} feloszlott"),
+            format!("{team}'s unit in {region} was disbanded"),
+        ],
+        Event::Captured {
+            team,
+            region,
+            previous,
+        } => match previous {
+            Some(previous) => lang![language =>
+                format!("{team} elfoglalta {previous} bázisát itt: {region}"),
+                format!("{team} captured the base in {region} from {previous}"),
+            ],
+            None => lang![language =>
+                format!("{team} elfoglalta a bázist itt: {region}"),
+                format!("{team} captured the base in {region}"),
+            ],
+        },
+    }
+}
@@ -5,6 +5,7 @@
 //! than units.
 //! Units may be Simple or Super.
 //! Super units are granted every 3 bases + 1 for having all home bases
+//! Units driven out of their region must retreat to a free neighboring region, or are disbanded.
 //!
 //! # Unit types:
 //! * Tank:
@@ -45,7 +46,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::region::Region;
+use super::region::{Border, Region, RegionType};
 use super::team::Team;
 use std::rc::Rc;
 
@@ -59,9 +60,236 @@ pub enum UnitType {
     Artillery,
 }
 
+/// The kind of regions and borders a unit may move or support on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Terrain {
+    Land,
+    Sea,
+    Any,
+}
+
+impl Terrain {
+    /// Whether a unit on this terrain may occupy the given type of region
+    pub fn occupies(&self, region_type: RegionType) -> bool {
+        use Terrain::*;
+        !matches!(
+            (self, region_type),
+            (Land, RegionType::Sea) | (Sea, RegionType::Land)
+        )
+    }
+
+    /// Whether a unit on this terrain may cross the given border
+    pub fn crosses(&self, border: &Border) -> bool {
+        use Terrain::*;
+        matches!(
+            (self, border),
+            (Any, _)
+                | (_, Border::Shore)
+                | (Land, Border::Land)
+                | (Sea, Border::Sea | Border::Strait(_))
+        )
+    }
+}
+
+/// Extra strength a unit has when attacking certain regions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bonus {
+    /// Strength of the unit when the bonus applies
+    pub strength: usize,
+    /// Type of the attacked region
+    pub target: RegionType,
+    /// Whether the attacked region must share a border with the unit's region
+    pub neighboring: bool,
+}
+
+/// Everything which sets one type of unit apart from another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub is_super: bool,
+    pub strength: usize,
+    pub attack_bonus: Option<Bonus>,
+    pub move_range: usize,
+    pub move_terrain: Terrain,
+    pub support_range: usize,
+    pub support_terrain: Terrain,
+}
+
+impl UnitType {
+    /// The rules of each unit type, as listed in the module documentation
+    pub fn stats(&self) -> Stats {
+        use Terrain::*;
+        use UnitType::*;
+        let simple = Stats {
+            is_super: false,
+            strength: 1,
+            attack_bonus: None,
+            move_range: 1,
+            move_terrain: Land,
+            support_range: 1,
+            support_terrain: Any,
+        };
+        match self {
+            Tank => simple,
+            Ship => Stats {
+                move_terrain: Sea,
+                support_terrain: Sea,
+                ..simple
+            },
+            Plane => Stats {
+                is_super: true,
+                move_range: 2,
+                move_terrain: Any,
+                ..simple
+            },
+            Supertank => Stats {
+                is_super: true,
+                strength: 2,
+                ..simple
+            },
+            Submarine => Stats {
+                is_super: true,
+                attack_bonus: Some(Bonus {
+                    strength: 2,
+                    target: RegionType::Sea,
+                    neighboring: true,
+                }),
+                move_range: 2,
+                move_terrain: Sea,
+                support_terrain: Sea,
+                ..simple
+            },
+            Artillery => Stats {
+                is_super: true,
+                support_range: 2,
+                ..simple
+            },
+        }
+    }
+
+    pub fn is_super(&self) -> bool {
+        self.stats().is_super
+    }
+
+    /// The simple unit type a super unit is upgraded from
+    pub fn simple(&self) -> UnitType {
+        use UnitType::*;
+        match self {
+            Tank | Supertank | Artillery | Plane => Tank,
+            Ship | Submarine => Ship,
+        }
+    }
+
+    /// Whether a unit of this type may be upgraded or downgraded into the other type
+    pub fn transforms_into(&self, into: UnitType) -> bool {
+        if self.is_super() {
+            into == self.simple()
+        } else {
+            into.is_super() && into.simple() == *self
+        }
+    }
+
+    /// Strength when holding, supporting, or bombarding
+    pub fn strength(&self) -> usize {
+        self.stats().strength
+    }
+
+    /// Strength when attacking a region of the given type, through the given border if it is a
+    /// neighbor
+    pub fn attack_strength(&self, target: RegionType, border: Option<&Border>) -> usize {
+        let stats = self.stats();
+        match stats.attack_bonus {
+            Some(bonus) if bonus.target == target && (border.is_some() || !bonus.neighboring) => {
+                bonus.strength
+            }
+            _ => stats.strength,
+        }
+    }
+
+    pub fn move_range(&self) -> usize {
+        self.stats().move_range
+    }
+
+    pub fn move_terrain(&self) -> Terrain {
+        self.stats().move_terrain
+    }
+
+    pub fn support_range(&self) -> usize {
+        self.stats().support_range
+    }
+
+    pub fn support_terrain(&self) -> Terrain {
+        self.stats().support_terrain
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Unit {
     unit_type: UnitType,
     region: Rc<Region>,
     owner: Rc<Team>,
 }
+
+impl Unit {
+    pub fn new(unit_type: UnitType, region: Rc<Region>, owner: Rc<Team>) -> Self {
+        Unit {
+            unit_type,
+            region,
+            owner,
+        }
+    }
+
+    pub fn unit_type(&self) -> UnitType {
+        self.unit_type
+    }
+
+    pub fn region(&self) -> &Rc<Region> {
+        &self.region
+    }
+
+    pub fn owner(&self) -> &Rc<Team> {
+        &self.owner
+    }
+
+    /// Moves the unit to another region
+    pub fn move_to(&mut self, region: Rc<Region>) {
+        self.region = region
+    }
+
+    /// Upgrades or downgrades the unit
+    pub fn transform(&mut self, into: UnitType) {
+        self.unit_type = into
+    }
+}
+
+/// A unit driven out of its region by an attack, which must retreat, or be disbanded
+#[derive(Debug, Clone)]
+pub struct Dislodged {
+    unit: Unit,
+    attacker: String,
+    retreats: Vec<String>,
+}
+
+impl Dislodged {
+    pub fn new(unit: Unit, attacker: String, retreats: Vec<String>) -> Self {
+        Dislodged {
+            unit,
+            attacker,
+            retreats,
+        }
+    }
+
+    /// The unit, still on the region it was driven out of
+    pub fn unit(&self) -> &Unit {
+        &self.unit
+    }
+
+    /// The region the dislodging attack came from
+    pub fn attacker(&self) -> &str {
+        &self.attacker
+    }
+
+    /// The regions the unit may retreat to
+    pub fn retreats(&self) -> &[String] {
+        &self.retreats
+    }
+}
@@ -0,0 +1,67 @@
+//! Games end once a team wins, or the turn limit is reached
+//!
+//! A team wins by holding enough bases (if a base-majority threshold is set), or by being the
+//! only team left with any bases or units (if elimination ends the game). Once the turn limit is
+//! reached, the team holding the most bases wins, or the leading teams draw.
+
+use serde::{Deserialize, Serialize};
+
+/// The conditions under which a game ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndConditions {
+    /// The game ends in the first winter after this many turns were played
+    pub turn_limit: Option<usize>,
+    /// A team holding at least this many bases wins
+    pub base_majority: Option<usize>,
+    /// The last team with any bases or units left wins
+    pub elimination: bool,
+}
+
+impl Default for EndConditions {
+    fn default() -> Self {
+        EndConditions {
+            turn_limit: None,
+            base_majority: None,
+            elimination: true,
+        }
+    }
+}
+
+/// How well a team is doing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    pub team: String,
+    pub bases: usize,
+    /// Units on the map, and dislodged units waiting to retreat
+    pub units: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    #[default]
+    Ongoing,
+    Won(String),
+    /// The turn limit was reached, and the teams hold the same number of bases
+    Drawn(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standings {
+    /// Teams ordered by the number of bases, then units they have
+    pub teams: Vec<Standing>,
+    pub outcome: Outcome,
+}
+
+impl Standings {
+    pub fn is_over(&self) -> bool {
+        self.outcome != Outcome::Ongoing
+    }
+
+    /// The winning team, if there is one yet
+    pub fn winner(&self) -> Option<&str> {
+        match &self.outcome {
+            Outcome::Won(team) => Some(team),
+            _ => None,
+        }
+    }
+}
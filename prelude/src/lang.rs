@@ -23,9 +23,17 @@ fn langs() -> Vec<Language> {
 pub static mut LANGUAGE: Language = Hungarian;
 
 /// Multilingual String macro, input &str's in the given order (currently Hungarian then English),
-/// and this will return the appropriate String depending on the state of the LANGUAGE global variable
+/// and this will return the appropriate String depending on the state of the LANGUAGE global variable.
+/// The language may also be given explicitly, as `lang![language => hungarian, english]`, for
+/// output which must not depend on the global state (eg. output of servers).
 #[macro_export]
 macro_rules! lang {
+    ($language:expr => $hungarian:expr, $english:expr $(,)?) => {
+        match $language {
+            $crate::lang::Language::Hungarian => $hungarian.to_string(),
+            $crate::lang::Language::English => $english.to_string(),
+        }
+    };
     ($hungarian:expr, $english:expr $(,)?) => {
         match unsafe { $crate::lang::LANGUAGE } {
             $crate::lang::Language::Hungarian => $hungarian.to_string(),
@@ -18,12 +18,12 @@
 //!
 //! ## DB
 //!
-//! Provides the Database trait and its associated methods. Currently only supports writing to
-//! Legacy "databases", which are the .hmap files which were used in ye olden days of the Haskell
-//! version of this program. I will need to create reading from .hmap-s as well for the sake of
-//! testing based on the first online game, but I hope not to need it for too long.
+//! Provides the Database trait, for loading and saving games and their orders, wherever they are
+//! stored. Supports reading and writing Legacy "databases", which are the .hmap files which were
+//! used in ye olden days of the Haskell version of this program, for the sake of testing based on
+//! the first online game, but I hope not to need them for too long, and plain JSON files.
 //!
-//! SurrealDB support is upcoming, and will be the primary database for the game.
+//! SurrealDB is the primary database for the game.
 //!
 //! ## Lang
 //!
@@ -0,0 +1,215 @@
+//! Reading legacy .hmap files
+
+#![cfg(feature = "ssr")]
+
+use std::rc::Rc;
+
+use prelude::db::legacy::{parse, Legacy, LegacyError, LegacyErrorKind};
+use prelude::draw::Color;
+use prelude::game::{
+    phase::Phase,
+    region::RegionType,
+    team::Team,
+    unit::{Unit, UnitType},
+    State,
+};
+
+/// A game of the Haskell version: red's home base is in Buda, and it took Pest
+const GAME: &str = r##"Név: Első
+Lépések: 2
+Tengeri mezők körvonalának Színe: #0000ff
+Szárazföld körvonal Szín: #000000
+---
+0,"blue","#0000aa",[]
+1,"red","#aa0000",[(0,True),(2,False)]
+---
+0,0,1,2
+1,1,1,1
+---
+0,2,"Buda","#aa0000",(0,0),"M 0,0 M 1,0 M 1,1 Z"
+1,0,"Duna","#8888ff",(1,0),"M 1,0 M 2,0 M 2,1 Z"
+2,2,"Pest","#dddddd",(2,0),"M 2,0 M 3,0 M 3,1 Z""##;
+
+/// The game, with the given line replaced
+fn with_line(number: usize, line: &str) -> String {
+    GAME.lines()
+        .enumerate()
+        .map(|(i, original)| if i + 1 == number { line } else { original })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn error(content: &str) -> LegacyError {
+    match parse(content) {
+        Ok(_) => panic!("the file should not be read"),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn reads_a_game() {
+    let state = parse(GAME).expect("the game is valid");
+    assert_eq!(state.turn, 2);
+    assert_eq!(state.phase, Phase::SpringMoves);
+    assert_eq!(state.water_stroke, Color::new(0, 0, 255));
+    assert_eq!(state.land_stroke, Color::new(0, 0, 0));
+
+    let teams: Vec<_> = state
+        .teams()
+        .iter()
+        .map(|team| team.name().as_str())
+        .collect();
+    assert_eq!(teams, ["blue", "red"]);
+    let red = &state.teams()[1];
+
+    let regions = state.regions();
+    assert_eq!(regions.node_count(), 3);
+    assert_eq!(regions.edge_count(), 0);
+    let (buda, duna, pest) = (&regions[0], &regions[1], &regions[2]);
+    assert_eq!(buda.name(), "Buda");
+    assert_eq!(buda.region_type(), RegionType::Land);
+    assert_eq!(duna.region_type(), RegionType::Sea);
+    assert!(buda.is_home_base_of(red));
+    assert!(!pest.is_home_base_of(red));
+    assert_eq!(pest.owner().as_deref(), Some(red.as_ref()));
+    assert!(!duna.has_base());
+
+    let units = state.units();
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0].unit_type(), UnitType::Tank);
+    assert_eq!(units[0].region().name(), "Pest");
+    assert_eq!(units[1].unit_type(), UnitType::Ship);
+    assert_eq!(units[1].owner().as_ref(), red.as_ref());
+}
+
+#[test]
+fn reads_english_labels() {
+    let content = with_line(1, "Name: First");
+    let content = content.replace("Lépések", "Steps");
+    assert!(parse(&content).is_ok());
+}
+
+#[test]
+fn new_games_start_with_builds() {
+    let state = parse(&with_line(2, "Lépések: 0")).expect("the game is valid");
+    assert_eq!(state.phase, Phase::WinterBuilds);
+}
+
+#[test]
+fn writes_every_unit_type() {
+    use UnitType::*;
+    let game = parse(GAME).expect("the game is valid");
+    // Teams are numbered by name in the file, whichever order the state has them in
+    let teams = game.teams().iter().rev().cloned().collect();
+    let regions = game.regions().clone();
+    let mut state = State::new(teams, regions, game.water_stroke, game.land_stroke);
+    state.turn = game.turn;
+    state.phase = game.phase;
+    let types = [Tank, Ship, Plane, Supertank, Submarine, Artillery];
+    for (i, unit_type) in types.into_iter().enumerate() {
+        let region = Rc::clone(&state.regions()[i as u32 % 3]);
+        let team = Rc::clone(&state.teams()[i % 2]);
+        state.units_mut().push(Unit::new(unit_type, region, team));
+    }
+
+    let mut legacy = Legacy::new("Első".into());
+    legacy
+        .read_from_state(&state)
+        .expect("the state can be written");
+    let read = legacy.to_state().expect("the written file is valid");
+
+    let units = |state: &State| -> Vec<_> {
+        state
+            .units()
+            .iter()
+            .map(|unit| {
+                let team = unit.owner().name().to_string();
+                (unit.unit_type(), team, unit.region().name().to_string())
+            })
+            .collect()
+    };
+    assert_eq!(units(&read), units(&state));
+    assert_eq!(read.canonical_hash(), state.canonical_hash());
+}
+
+#[test]
+fn units_of_other_games_are_not_written() {
+    let state = parse(GAME).expect("the game is valid");
+    let green = Rc::new(Team::new("green".into(), Color::new(0, 170, 0)));
+    let buda = Rc::clone(&state.regions()[0]);
+    state
+        .units_mut()
+        .push(Unit::new(UnitType::Tank, buda, green));
+
+    let mut legacy = Legacy::new("Első".into());
+    let err = legacy
+        .read_from_state(&state)
+        .expect_err("green is not in the game");
+    assert_eq!(
+        err.to_string(),
+        "Unit 2 belongs to green, which is not in the game"
+    );
+}
+
+#[test]
+fn errors_are_numbered_by_line() {
+    use LegacyErrorKind::*;
+    let cases = [
+        (
+            with_line(1, "Játék: Első"),
+            1,
+            Unknown("Játék: Első".into()),
+        ),
+        (
+            with_line(2, "Lépések: kettő"),
+            2,
+            Malformed("a number of steps"),
+        ),
+        (with_line(7, "1,\"red\",[]"), 7, Malformed("")),
+        (
+            with_line(7, "2,\"red\",\"#aa0000\",[]"),
+            7,
+            Index {
+                expected: 1,
+                found: 2,
+            },
+        ),
+        (
+            with_line(7, "1,\"red\",\"#aa0000\",[(1,True)]"),
+            7,
+            NoBase("Duna".into()),
+        ),
+        (
+            with_line(7, "1,\"red\",\"#aa0000\",[(5,True)]"),
+            7,
+            NoRegion(5),
+        ),
+        (with_line(9, "0,6,1,2"), 9, Malformed("")),
+        (with_line(9, "0,0,2,2"), 9, NoTeam(2)),
+        (
+            with_line(13, "1,3,\"Duna\",\"#8888ff\",(1,0),\"Z\""),
+            13,
+            Malformed(""),
+        ),
+        (
+            with_line(14, "2,2,\"Pest\",\"#dddddd\",(2,0),\"M 2,0 L 3,0 Z\""),
+            14,
+            Malformed(""),
+        ),
+        (GAME.to_owned() + "\n---", 15, Unknown("---".into())),
+        (
+            GAME.split("\n---\n0,2").next().unwrap().into(),
+            11,
+            Missing(""),
+        ),
+    ];
+    for (content, line, kind) in cases {
+        let err = error(&content);
+        assert_eq!(err.line, line, "{err}");
+        // What was expected is only described for people
+        match (&err.kind, &kind) {
+            (Malformed(_), Malformed(_)) | (Missing(_), Missing(_)) => (),
+            _ => assert_eq!(err.kind, kind, "{err}"),
+        }
+    }
+}
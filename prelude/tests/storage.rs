@@ -0,0 +1,197 @@
+//! Storing games in files, via the Database trait
+
+#![cfg(feature = "ssr")]
+
+use std::{cell::RefCell, env, fs, path::PathBuf, rc::Rc};
+
+use petgraph::{csr::Csr, Undirected};
+use prelude::db::{legacy::Legacy, Database, JsonFile};
+use prelude::draw::{Color, Point, Shape};
+use prelude::game::{
+    order::{Attack, OrderKind, Stay},
+    phase::Phase,
+    region::{Base, Border, Region, RegionType},
+    team::Team,
+    unit::{Dislodged, Unit, UnitType},
+    State,
+};
+
+/// A land region with red's home base, a sea, and a field, which is all legacy files can hold.
+/// Units are given by the tests which store them.
+fn game() -> State {
+    let red = Rc::new(Team::new("red".into(), Color::new(255, 0, 0)));
+    let blue = Rc::new(Team::new("blue".into(), Color::new(0, 0, 255)));
+    let mut regions: Csr<Rc<Region>, Border, Undirected> = Csr::new();
+    for (i, (name, region_type, color)) in [
+        ("castle", RegionType::Land, red.color()),
+        ("sea", RegionType::Sea, Color::new(128, 128, 255)),
+        ("field", RegionType::Land, Color::white()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let base = (name == "castle").then(|| {
+            let mut base = Base::new();
+            base.set(Rc::clone(&red));
+            RefCell::new(base)
+        });
+        let x = i as f32;
+        let shape = Shape::new(&[
+            Point::new(x, 0.0),
+            Point::new(x + 1.0, 0.0),
+            Point::new(x + 1.0, 1.0),
+        ]);
+        let region = Region::new(
+            name.into(),
+            region_type,
+            base,
+            shape,
+            Point::new(x + 0.75, 0.25),
+            color,
+        )
+        .expect("only the castle has a base");
+        regions.add_node(Rc::new(region));
+    }
+    regions.add_edge(0, 1, Border::Shore);
+    regions.add_edge(0, 2, Border::Land);
+
+    let mut state = State::new(vec![red, blue], regions, Color::black(), Color::black());
+    state.turn = 2;
+    state.phase = Phase::SpringMoves;
+    state
+}
+
+/// An empty directory to store games in
+fn directory(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("hadijatek_{name}"));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).expect("the directory can be created");
+    directory
+}
+
+/// Saves, then loads orders
+async fn orders_round_trip(db: &mut impl Database) -> anyhow::Result<()> {
+    assert!(db.load_orders().await?.is_empty());
+    let orders = vec![
+        OrderKind::Stay(Stay::new("castle".into())),
+        OrderKind::Attack(Attack::new("field".into(), "castle".into())),
+    ];
+    db.save_orders(&orders).await?;
+    assert_eq!(db.load_orders().await?, orders);
+    db.save_orders(&[]).await?;
+    assert!(db.load_orders().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn json_round_trip() -> anyhow::Result<()> {
+    let mut db = JsonFile::new("game".into(), directory("json_round_trip"));
+
+    let mut state = game();
+    let red = Rc::clone(&state.teams()[0]);
+    let blue = Rc::clone(&state.teams()[1]);
+    let region = |i: u32| Rc::clone(&state.regions()[i]);
+    let units = vec![
+        Unit::new(UnitType::Tank, region(0), Rc::clone(&red)),
+        Unit::new(UnitType::Ship, region(1), Rc::clone(&red)),
+    ];
+    let tank = Unit::new(UnitType::Tank, region(2), blue);
+    state.units_mut().extend(units);
+    state
+        .dislodged_mut()
+        .push(Dislodged::new(tank, "castle".into(), vec![]));
+    state.phase = Phase::SpringRetreats;
+    let hash = state.canonical_hash();
+    db.save_state(&state).await?;
+
+    let read = db.load_state().await?;
+    assert_eq!(read.regions().node_count(), 3);
+    assert_eq!(read.regions().edge_count(), 2);
+    assert!(matches!(read.border(0, 1), Some(Border::Shore)));
+    assert_eq!(read.regions()[0].region_type(), RegionType::Land);
+    assert_eq!(read.dislodged().len(), 1);
+    assert_eq!(read.canonical_hash(), hash);
+
+    orders_round_trip(&mut db).await
+}
+
+#[tokio::test]
+async fn legacy_round_trip() -> anyhow::Result<()> {
+    let mut db = Legacy::new("game".into()).directory(directory("legacy_round_trip"));
+
+    let state = game();
+    let hash = state.canonical_hash();
+    db.save_state(&state).await?;
+
+    // Legacy files do not list borders
+    let read = db.load_state().await?;
+    assert_eq!(read.regions().node_count(), 3);
+    assert_eq!(read.regions().edge_count(), 0);
+    assert!(read.regions()[0].is_home_base_of(&read.teams()[1]));
+    assert_eq!(read.regions()[2].pole(), Point::new(2.75, 0.25));
+    assert_eq!(read.canonical_hash(), hash);
+
+    orders_round_trip(&mut db).await
+}
+
+#[tokio::test]
+async fn legacy_stores_only_games_between_turns() -> anyhow::Result<()> {
+    let mut db = Legacy::new("game".into()).directory(directory("legacy_between_turns"));
+
+    let mut state = game();
+    state.phase = Phase::FallMoves;
+    assert!(db.save_state(&state).await.is_err());
+
+    let tank = Unit::new(
+        UnitType::Tank,
+        Rc::clone(&state.regions()[2]),
+        Rc::clone(&state.teams()[1]),
+    );
+    state
+        .dislodged_mut()
+        .push(Dislodged::new(tank, "castle".into(), vec![]));
+    state.phase = Phase::FallRetreats;
+    assert!(db.save_state(&state).await.is_err());
+    // Dislodged units are refused in any phase
+    state.phase = Phase::SpringMoves;
+    assert!(db.save_state(&state).await.is_err());
+
+    assert!(db.list_games().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn legacy_loads_the_latest_turn() -> anyhow::Result<()> {
+    let mut db = Legacy::new("game".into()).directory(directory("legacy_latest_turn"));
+    let mut state = game();
+    for turn in [9, 10] {
+        state.turn = turn;
+        db.save_state(&state).await?;
+    }
+    assert_eq!(db.load_state().await?.turn, 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn games_are_listed() -> anyhow::Result<()> {
+    let json = directory("json_games");
+    for name in ["second", "first"] {
+        let mut db = JsonFile::new(name.into(), &json);
+        db.save_state(&game()).await?;
+        db.save_orders(&[]).await?;
+    }
+    let db = JsonFile::new("third".into(), &json);
+    assert_eq!(db.list_games().await?, ["first", "second"]);
+
+    let legacy = directory("legacy_games");
+    let mut state = game();
+    for (name, turn) in [("second_game", 0), ("first", 0), ("second_game", 1)] {
+        let mut db = Legacy::new(name.into()).directory(&legacy);
+        state.turn = turn;
+        db.save_state(&state).await?;
+    }
+    let db = Legacy::new("third".into()).directory(&legacy);
+    assert_eq!(db.list_games().await?, ["first", "second_game"]);
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! Round trips of game states through SurrealDB
+//!
+//! These tests use an in-memory database, unless one is given like it is to the adjudicator, via
+//! the `HADIJATEK_DB_ADDRESS`, `HADIJATEK_DB_NAMESPACE`, `HADIJATEK_DB_USERNAME`, and
+//! `HADIJATEK_DB_PASSWORD` environment variables.
+
+#![cfg(feature = "ssr")]
+
+use std::{cell::RefCell, env, rc::Rc};
+
+use petgraph::{csr::Csr, Undirected};
+use prelude::db::{self, Database, Prelude, Surrealdb};
+use prelude::draw::{Color, Point, Shape};
+use prelude::game::{
+    order::{OrderKind, Stay},
+    phase::Phase,
+    region::{Base, Border, Region, RegionType},
+    team::Team,
+    unit::{Dislodged, Unit, UnitType},
+    victory::{EndConditions, Outcome},
+    State,
+};
+
+/// A coast with a base, a sea, and a land region, with a Tank and Plane of one team, and a
+/// dislodged Ship of the other
+fn game() -> State {
+    let red = Rc::new(Team::new("red".into(), Color::new(255, 0, 0)));
+    let blue = Rc::new(Team::new("blue".into(), Color::new(0, 0, 255)));
+    let mut regions: Csr<Rc<Region>, Border, Undirected> = Csr::new();
+    for (name, region_type, base) in [
+        ("field", RegionType::Land, false),
+        ("coast", RegionType::Shore, true),
+        ("sea", RegionType::Sea, false),
+    ] {
+        let base = base.then(|| {
+            let mut base = Base::new();
+            base.set(Rc::clone(&red));
+            RefCell::new(base)
+        });
+        let region = Region::new(
+            name.into(),
+            region_type,
+            base,
+            Shape::new(&[]),
+            Point::new(0.0, 0.0),
+            Color::white(),
+        )
+        .expect("only the coast has a base");
+        regions.add_node(Rc::new(region));
+    }
+    regions.add_edge(0, 1, Border::Land);
+    regions.add_edge(1, 2, Border::Sea);
+
+    let mut state = State::new(
+        vec![Rc::clone(&red), Rc::clone(&blue)],
+        regions,
+        Color::black(),
+        Color::black(),
+    );
+    state.turn = 3;
+    state.phase = Phase::FallRetreats;
+
+    let region = |i: usize| Rc::clone(&state.regions()[i as u32]);
+    let units = vec![
+        Unit::new(UnitType::Tank, region(1), Rc::clone(&red)),
+        Unit::new(UnitType::Plane, region(2), Rc::clone(&red)),
+    ];
+    let ship = Unit::new(UnitType::Ship, region(1), Rc::clone(&blue));
+    state.units_mut().extend(units);
+    state
+        .dislodged_mut()
+        .push(Dislodged::new(ship, "field".into(), vec!["sea".into()]));
+    state
+}
+
+/// The game called `name`, in the database to test with
+async fn database(name: &str) -> anyhow::Result<Surrealdb> {
+    let address = env::var("HADIJATEK_DB_ADDRESS").unwrap_or("mem://".into());
+    let namespace = env::var("HADIJATEK_DB_NAMESPACE").unwrap_or("hadijatek".into());
+    let username = env::var("HADIJATEK_DB_USERNAME").unwrap_or("hadijatek".into());
+    let password = env::var("HADIJATEK_DB_PASSWORD").unwrap_or("hadijatek".into());
+    let db = db::connect(&address, &namespace, &username, &password).await?;
+    Ok(Surrealdb::new(name.into(), db))
+}
+
+#[tokio::test]
+async fn units_round_trip() -> anyhow::Result<()> {
+    let mut db = database("test_units_round_trip").await?;
+
+    let state = game();
+    let hash = state.canonical_hash();
+    db.write(&state).await?;
+    let read = db.read().await?;
+
+    assert_eq!(read.units().len(), 2);
+    assert_eq!(read.dislodged().len(), 1);
+    let ship = read
+        .dislodged_from("coast")
+        .expect("the Ship was dislodged");
+    assert_eq!(ship.unit().unit_type(), UnitType::Ship);
+    assert_eq!(ship.retreats(), ["sea"]);
+    assert_eq!(read.canonical_hash(), hash);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rewrite_in_place() -> anyhow::Result<()> {
+    let mut db = database("test_rewrite_in_place").await?;
+    db.write(&game()).await?;
+
+    // The next turn: the Ship is gone, the Tank moved, and blue took the coast
+    let mut state = game();
+    state.dislodged_mut().clear();
+    let field = Rc::clone(&state.regions()[0]);
+    state.units_mut()[0].move_to(field);
+    let blue = Rc::clone(&state.teams()[1]);
+    state.regions()[1].capture(blue);
+    state.turn = 4;
+    state.phase = Phase::SpringMoves;
+    let hash = state.canonical_hash();
+    db.write(&state).await?;
+
+    let read = db.read().await?;
+    assert_eq!(read.teams().len(), 2);
+    assert_eq!(read.regions().node_count(), 3);
+    assert_eq!(read.regions().edge_count(), 2);
+    assert!(read.dislodged().is_empty());
+    assert_eq!(read.canonical_hash(), hash);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn orders_and_games() -> anyhow::Result<()> {
+    let mut db = database("test_orders_and_games").await?;
+    db.save_state(&game()).await?;
+
+    assert!(db.load_orders().await?.is_empty());
+    let orders = vec![OrderKind::Stay(Stay::new("coast".into()))];
+    db.save_orders(&orders).await?;
+    assert_eq!(db.load_orders().await?, orders);
+
+    let games = db.list_games().await?;
+    assert!(games.iter().any(|game| game == "test_orders_and_games"));
+
+    Ok(())
+}
+
+#[test]
+fn old_preludes() -> anyhow::Result<()> {
+    // Stored before games had end conditions
+    let prelude: Prelude = serde_json::from_str(
+        r#"{"turn": 5, "phase": "FallMoves", "water_stroke": [0, 0, 0], "land_stroke": [0, 0, 0]}"#,
+    )?;
+    assert_eq!(prelude.turn, 5);
+    assert_eq!(prelude.phase, Phase::FallMoves);
+    assert_eq!(prelude.end_conditions, EndConditions::default());
+    assert_eq!(prelude.outcome, Outcome::Ongoing);
+
+    // Stored before games had phases
+    let prelude: Prelude = serde_json::from_str(
+        r#"{"turn": 2, "water_stroke": [0, 0, 0], "land_stroke": [0, 0, 0]}"#,
+    )?;
+    assert_eq!(prelude.turn, 2);
+    assert_eq!(prelude.phase, Phase::SpringMoves);
+    assert_eq!(prelude.end_conditions, EndConditions::default());
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use leptos::{ev::MouseEvent, *};
 use leptos_router::*;
+use prelude::game::{phase::Phase, victory::Outcome};
 use wasm_bindgen::JsCast;
 use web_sys::SvgElement;
 
@@ -32,11 +33,150 @@ pub fn NoGamePage() -> impl IntoView {
     }
 }
 
+/// The stored game called `game`, on a connection of its own
+///
+/// The connection shared by the server's requests stays on the database of users and sessions, so
+/// a game's database is only ever used on a new connection, once the game is known to exist.
+#[cfg(feature = "ssr")]
+async fn stored_game(game: String) -> Result<prelude::db::Surrealdb, ServerFnError> {
+    use crate::config::config;
+    use prelude::db::{connect, Database, Surrealdb};
+
+    let server_error = |err: anyhow::Error| ServerFnError::ServerError(err.to_string());
+    let database = &config().database;
+    let db = connect(
+        &database.address,
+        &database.namespace,
+        &database.username,
+        &database.password,
+    )
+    .await
+    .map_err(server_error)?;
+
+    let stored = Surrealdb::new(game.clone(), db);
+    let games = stored.list_games().await.map_err(server_error)?;
+    if !games.contains(&game) {
+        return Err(ServerFnError::ServerError("No such game.".into()));
+    }
+    Ok(stored)
+}
+
+/// The phase the game is in
+#[server(GetPhase, "/api")]
+pub async fn get_phase(game: String) -> Result<Phase, ServerFnError> {
+    let prelude = stored_game(game)
+        .await?
+        .read_prelude()
+        .await
+        .map_err(|err| ServerFnError::ServerError(err.to_string()))?;
+    let prelude = prelude.ok_or_else(|| ServerFnError::ServerError("No such game.".into()))?;
+
+    Ok(prelude.phase)
+}
+
+/// Shows which phase the game is in
+#[component]
+pub fn PhaseInfo(phase: Phase) -> impl IntoView {
+    use Phase::*;
+    let (hu, en) = match phase {
+        SpringMoves => ("Tavaszi lépések", "Spring moves"),
+        SpringRetreats => ("Tavaszi visszavonulások", "Spring retreats"),
+        FallMoves => ("Őszi lépések", "Fall moves"),
+        FallRetreats => ("Őszi visszavonulások", "Fall retreats"),
+        WinterBuilds => ("Téli toborzás", "Winter builds"),
+    };
+
+    view! {
+        <p><Lang hu=hu en=en/></p>
+    }
+}
+
+/// Whether the game is over, and who won it
+#[server(GetOutcome, "/api")]
+pub async fn get_outcome(game: String) -> Result<Outcome, ServerFnError> {
+    let prelude = stored_game(game)
+        .await?
+        .read_prelude()
+        .await
+        .map_err(|err| ServerFnError::ServerError(err.to_string()))?;
+    let prelude = prelude.ok_or_else(|| ServerFnError::ServerError("No such game.".into()))?;
+
+    Ok(prelude.outcome)
+}
+
+/// Announces the end of the game, if it is over
+#[component]
+pub fn GameOver(outcome: Outcome) -> impl IntoView {
+    match outcome {
+        Outcome::Ongoing => ().into_view(),
+        Outcome::Won(team) => view! {
+            <h2><Lang hu="Vége a játéknak!" en="Game over!"/></h2>
+            <p><Lang hu=format!("{team} nyert.") en=format!("{team} won.")/></p>
+        }
+        .into_view(),
+        Outcome::Drawn(teams) => {
+            let teams = teams.join(", ");
+            view! {
+                <h2><Lang hu="Vége a játéknak!" en="Game over!"/></h2>
+                <p><Lang hu=format!("Döntetlen: {teams}") en=format!("Draw between {teams}")/></p>
+            }
+            .into_view()
+        }
+    }
+}
+
+/// The report of the last adjudicated phase, in Hungarian and English, if there is one yet
+#[server(GetReport, "/api")]
+pub async fn get_report(game: String) -> Result<Option<(String, String)>, ServerFnError> {
+    use prelude::lang::Language;
+
+    let report = stored_game(game)
+        .await?
+        .read_report()
+        .await
+        .map_err(|err| ServerFnError::ServerError(err.to_string()))?;
+
+    Ok(report.map(|report| {
+        (
+            report.text(Language::Hungarian),
+            report.text(Language::English),
+        )
+    }))
+}
+
+/// Shows what happened in the last adjudicated phase
+#[component]
+pub fn TurnReport(hu: String, en: String) -> impl IntoView {
+    view! {
+        <h2><Lang hu="Az előző szakasz" en="The last phase"/></h2>
+        <pre><Lang hu=hu en=en/></pre>
+    }
+}
+
 #[component]
 pub fn GamePage() -> impl IntoView {
     let params = use_params_map();
     let game = move || params.with(|p| p.get("game").cloned());
 
+    let phase = create_resource(game, |game| async move {
+        match game {
+            Some(game) => get_phase(game).await,
+            None => Err(ServerFnError::ServerError("No game.".into())),
+        }
+    });
+    let outcome = create_resource(game, |game| async move {
+        match game {
+            Some(game) => get_outcome(game).await,
+            None => Err(ServerFnError::ServerError("No game.".into())),
+        }
+    });
+    let report = create_resource(game, |game| async move {
+        match game {
+            Some(game) => get_report(game).await,
+            None => Err(ServerFnError::ServerError("No game.".into())),
+        }
+    });
+
     let (mouse_pos, set_mouse_pos) = create_signal((0, 0));
 
     let (from, set_from) = create_signal((0, 0));
@@ -100,6 +240,11 @@ pub fn GamePage() -> impl IntoView {
 
     view! {
         <h1>"Welcome to "{game}" Game Page!"</h1>
+        <Suspense fallback=||()>
+            {move || phase.get().and_then(Result::ok).map(|phase| view! { <PhaseInfo phase=phase/> })}
+            {move || outcome.get().and_then(Result::ok).map(|outcome| view! { <GameOver outcome=outcome/> })}
+            {move || report.get().and_then(Result::ok).flatten().map(|(hu, en)| view! { <TurnReport hu=hu en=en/> })}
+        </Suspense>
         <p>{move || format!("{:?}, {:?}, {:?}", mouse_pos(), from(), to())}</p>
         <div> // on:click=on_click on:mousemove=on_move>
         <svg viewBox="0 0 1000 500" xmlns="http://www.w3.org/2000/svg"
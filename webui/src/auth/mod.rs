@@ -8,14 +8,15 @@ pub mod token;
 
 cfg_if! { if #[cfg(feature = "ssr")] {
     use self::token::consume_token;
+    use crate::config::config;
     use surrealdb::Surreal;
     use surrealdb::sql::Value;
     use surrealdb::sql::Thing;
     use surrealdb::sql::Id;
-    use surrealdb::engine::remote::ws::Client;
+    use surrealdb::engine::any::Any;
     use axum_session_auth::{SessionSurrealPool, Authentication};
     use bcrypt::{hash, verify, DEFAULT_COST};
-    pub type AuthSession = axum_session_auth::AuthSession<User, String, SessionSurrealPool<Client>, Surreal<Client>>;
+    pub type AuthSession = axum_session_auth::AuthSession<User, String, SessionSurrealPool<Any>, Surreal<Any>>;
 }}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,16 +75,16 @@ cfg_if! { if #[cfg(feature = "ssr")] {
             }
         }
 
-        pub async fn get(id: &str, db: &Surreal<Client>) -> Option<Self> {
-            db.use_ns("hadijatek").use_db("auth").await.ok()?;
+        pub async fn get(id: &str, db: &Surreal<Any>) -> Option<Self> {
+            db.use_db(&config().database.name).await.ok()?;
 
             let s_user = db.select(("user", id)).await.ok()??;
 
             Some(User::from_surreal_user(s_user, id.to_string()))
         }
 
-        pub async fn get_from_username(name: &str, db: &Surreal<Client>) -> Option<Self> {
-            db.use_ns("hadijatek").use_db("auth").await.ok()?;
+        pub async fn get_from_username(name: &str, db: &Surreal<Any>) -> Option<Self> {
+            db.use_db(&config().database.name).await.ok()?;
 
             let mut id = db
                 .query("SELECT id FROM user WHERE username = $username")
@@ -97,8 +98,8 @@ cfg_if! { if #[cfg(feature = "ssr")] {
             Self::get(&id, db).await
         }
 
-        pub async fn name_taken(name: &str, db: &Surreal<Client>) -> Result<bool, surrealdb::Error> {
-            db.use_ns("hadijatek").use_db("auth").await?;
+        pub async fn name_taken(name: &str, db: &Surreal<Any>) -> Result<bool, surrealdb::Error> {
+            db.use_db(&config().database.name).await?;
 
             let mut id = db
                 .query("SELECT id FROM user WHERE username = $username")
@@ -112,10 +113,10 @@ cfg_if! { if #[cfg(feature = "ssr")] {
     }
 
     #[async_trait]
-    impl Authentication<User, String, Surreal<Client>> for User {
+    impl Authentication<User, String, Surreal<Any>> for User {
         async fn load_user(
             userid: String,
-            pool: Option<&Surreal<Client>>
+            pool: Option<&Surreal<Any>>
         ) -> Result<User, anyhow::Error> {
             let pool = pool.unwrap();
 
@@ -142,8 +143,8 @@ cfg_if! { if #[cfg(feature = "ssr")] {
             .ok_or_else(|| ServerFnError::ServerError("Auth session missing.".into()))
     }
 
-    pub fn db() -> Result<Surreal<Client>, ServerFnError> {
-        use_context::<Surreal<Client>>()
+    pub fn db() -> Result<Surreal<Any>, ServerFnError> {
+        use_context::<Surreal<Any>>()
             .ok_or_else(|| ServerFnError::ServerError("DB missing.".into()))
     }
 }}
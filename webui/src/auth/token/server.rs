@@ -1,16 +1,17 @@
 use super::*;
 use crate::auth::User;
+use crate::config::config;
 use axum_session_auth::SessionSurrealPool;
 use leptos::*;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use surrealdb::sql::Id;
 use surrealdb::Surreal;
 pub type AuthSession =
-    axum_session_auth::AuthSession<User, String, SessionSurrealPool<Client>, Surreal<Client>>;
+    axum_session_auth::AuthSession<User, String, SessionSurrealPool<Any>, Surreal<Any>>;
 
 /// Generates token on specified table
-pub async fn gen_token(table: &str, db: &Surreal<Client>) -> Result<String, ServerFnError> {
-    db.use_ns("hadijatek").use_db("auth").await?;
+pub async fn gen_token(table: &str, db: &Surreal<Any>) -> Result<String, ServerFnError> {
+    db.use_db(&config().database.name).await?;
 
     let token = Id::rand().to_raw();
 
@@ -36,9 +37,9 @@ pub async fn gen_token(table: &str, db: &Surreal<Client>) -> Result<String, Serv
 pub async fn delete_token(
     table: &str,
     token: &str,
-    db: &Surreal<Client>,
+    db: &Surreal<Any>,
 ) -> Result<(), ServerFnError> {
-    db.use_ns("hadijatek").use_db("auth").await?;
+    db.use_db(&config().database.name).await?;
 
     let _deleted_token: Option<Token> = db.delete((table, token)).await?;
 
@@ -50,7 +51,7 @@ pub async fn get_consumer<T: for<'a> Deserialize<'a> + Debug>(
     token_table: &str,
     token: &str,
     consumer_table: &str,
-    db: &Surreal<Client>,
+    db: &Surreal<Any>,
 ) -> Result<Option<(T, DateTime<Utc>)>, ServerFnError> {
     let query = format!("SELECT <-consume<-{consumer_table}.* FROM {token_table}:{token}");
     let mut result = db.query(query).await?;
@@ -103,7 +104,7 @@ pub async fn verify_token<T: for<'a> Deserialize<'a> + Debug>(
     token_table: &str,
     token: &str,
     consumer_table: &str,
-    db: &Surreal<Client>,
+    db: &Surreal<Any>,
 ) -> Result<(), ServerFnError> {
     let token_exists: Option<Token> = db.select((token_table, token)).await?;
 
@@ -131,9 +132,9 @@ pub async fn consume_token<T: for<'a> Deserialize<'a> + Debug>(
     token: &str,
     consumer_table: &str,
     consumer_id: &str,
-    db: &Surreal<Client>,
+    db: &Surreal<Any>,
 ) -> Result<(), ServerFnError> {
-    db.use_ns("hadijatek").use_db("auth").await?;
+    db.use_db(&config().database.name).await?;
 
     verify_token::<T>(token_table, token, consumer_table, db).await?;
 
@@ -155,12 +156,12 @@ type Consumer<T> = Option<(T, DateTime<Utc>)>;
 pub async fn get_tokens<T>(
     token_table: &str,
     consumer_table: &str,
-    db: &Surreal<Client>,
+    db: &Surreal<Any>,
 ) -> Result<Vec<(Token, Option<(T, DateTime<Utc>)>)>, ServerFnError>
 where
     T: Serialize + for<'a> Deserialize<'a> + std::fmt::Debug,
 {
-    db.use_ns("hadijatek").use_db("auth").await?;
+    db.use_db(&config().database.name).await?;
 
     let just_tokens: Vec<Token> = db.select(token_table).await?;
 
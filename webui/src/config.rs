@@ -0,0 +1,288 @@
+//! Server configuration
+//!
+//! Read from a TOML file, `hadijatek.toml` in the working directory, or the one at
+//! `HADIJATEK_CONFIG`, whose settings may be overridden by environment variables. Everything has
+//! a default, so neither the file nor the variables are required:
+//!
+//! ```toml
+//! log_level = "info"              # HADIJATEK_LOG_LEVEL: error, warn, info, debug, or trace
+//!
+//! [database]
+//! address = "127.0.0.1:8080"      # HADIJATEK_DB_ADDRESS, see prelude::db::connect
+//! namespace = "hadijatek"         # HADIJATEK_DB_NAMESPACE
+//! name = "auth"                   # HADIJATEK_DB_NAME: the database of users and sessions
+//! username = "hadijatek"          # HADIJATEK_DB_USERNAME
+//! password = "hadijatek"          # HADIJATEK_DB_PASSWORD
+//!
+//! [session]
+//! table = "axum_sessions"         # HADIJATEK_SESSION_TABLE
+//! cookie_name = "session"         # HADIJATEK_SESSION_COOKIE
+//! lifetime_days = 30              # HADIJATEK_SESSION_LIFETIME_DAYS
+//! secure = false                  # HADIJATEK_SESSION_SECURE: only send cookies over HTTPS
+//! ```
+
+use std::{env, fs, io::ErrorKind, path::PathBuf, str::FromStr, sync::OnceLock};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+const DEFAULT_PATH: &str = "hadijatek.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The configuration of the running server
+pub fn config() -> &'static Config {
+    CONFIG
+        .get()
+        .expect("the configuration is loaded at startup")
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Cannot read the configuration file {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("Cannot parse the configuration file {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("{name} should be {expected}, not {value:?}")]
+    Env {
+        name: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    #[error("Invalid configuration:\n  {}", .0.join("\n  "))]
+    Invalid(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub log_level: String,
+    pub database: DatabaseConfig,
+    pub session: SessionConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub address: String,
+    pub namespace: String,
+    /// The database of users and sessions
+    pub name: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionConfig {
+    pub table: String,
+    pub cookie_name: String,
+    pub lifetime_days: i64,
+    /// Only send the session cookie over HTTPS
+    pub secure: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            log_level: "info".into(),
+            database: DatabaseConfig::default(),
+            session: SessionConfig::default(),
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            address: "127.0.0.1:8080".into(),
+            namespace: "hadijatek".into(),
+            name: "auth".into(),
+            username: "hadijatek".into(),
+            password: "hadijatek".into(),
+        }
+    }
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            table: "axum_sessions".into(),
+            cookie_name: "session".into(),
+            lifetime_days: 30,
+            secure: false,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the configuration file, applies the environment's overrides, and validates the
+    /// result. A missing file is only an error if it was given by `HADIJATEK_CONFIG`.
+    pub fn load() -> Result<Config, ConfigError> {
+        let (path, required) = match env::var("HADIJATEK_CONFIG") {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from(DEFAULT_PATH), false),
+        };
+        let mut config = match fs::read_to_string(&path) {
+            Ok(file) => Config::parse(&file).map_err(|err| ConfigError::Parse(path, err))?,
+            Err(err) if err.kind() == ErrorKind::NotFound && !required => Config::default(),
+            Err(err) => return Err(ConfigError::Read(path, err)),
+        };
+        config.override_with(|name| env::var(name).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Makes this the configuration of the running server
+    pub fn set(self) -> &'static Config {
+        CONFIG.get_or_init(|| self)
+    }
+
+    /// Parses a configuration file; settings not in it keep their defaults
+    pub fn parse(file: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(file)
+    }
+
+    /// Overrides settings with the variables `env` knows of
+    pub fn override_with(
+        &mut self,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        let text = "text";
+        set(&mut self.log_level, "HADIJATEK_LOG_LEVEL", text, &env)?;
+        let database = &mut self.database;
+        set(&mut database.address, "HADIJATEK_DB_ADDRESS", text, &env)?;
+        set(
+            &mut database.namespace,
+            "HADIJATEK_DB_NAMESPACE",
+            text,
+            &env,
+        )?;
+        set(&mut database.name, "HADIJATEK_DB_NAME", text, &env)?;
+        set(&mut database.username, "HADIJATEK_DB_USERNAME", text, &env)?;
+        set(&mut database.password, "HADIJATEK_DB_PASSWORD", text, &env)?;
+        let session = &mut self.session;
+        set(&mut session.table, "HADIJATEK_SESSION_TABLE", text, &env)?;
+        set(
+            &mut session.cookie_name,
+            "HADIJATEK_SESSION_COOKIE",
+            text,
+            &env,
+        )?;
+        set(
+            &mut session.lifetime_days,
+            "HADIJATEK_SESSION_LIFETIME_DAYS",
+            "a whole number of days",
+            &env,
+        )?;
+        set(
+            &mut session.secure,
+            "HADIJATEK_SESSION_SECURE",
+            "true or false",
+            &env,
+        )?;
+        Ok(())
+    }
+
+    /// Checks every setting, listing all that are wrong
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        if self.log_level.parse::<log::Level>().is_err() {
+            problems.push(format!(
+                "log_level should be error, warn, info, debug, or trace, not {:?}",
+                self.log_level
+            ));
+        }
+
+        let database = &self.database;
+        let scheme = database.address.split_once("://").map(|(scheme, _)| scheme);
+        let remote = match scheme {
+            None | Some("ws" | "wss" | "http" | "https") => true,
+            Some("mem") => false,
+            // RocksDB is only built with the file feature
+            Some("file") => {
+                if !cfg!(feature = "file") {
+                    problems.push(
+                        "database.address is a file:// database, but the server was built \
+                        without the file feature"
+                            .into(),
+                    );
+                }
+                false
+            }
+            Some(scheme) => {
+                problems.push(format!(
+                    "database.address has an unknown scheme: {scheme}:// \
+                    (should be ws, wss, http, https, mem, or file)"
+                ));
+                false
+            }
+        };
+        if database.address.is_empty() {
+            problems.push("database.address is empty".into());
+        }
+        for (name, value) in [
+            ("database.namespace", &database.namespace),
+            ("database.name", &database.name),
+        ] {
+            if value.is_empty() {
+                problems.push(format!("{name} is empty"));
+            }
+        }
+        if remote {
+            for (name, value) in [
+                ("database.username", &database.username),
+                ("database.password", &database.password),
+            ] {
+                if value.is_empty() {
+                    problems.push(format!("{name} is empty, but is needed to sign in"));
+                }
+            }
+        }
+
+        let session = &self.session;
+        if session.table.is_empty() {
+            problems.push("session.table is empty".into());
+        }
+        let cookie_character = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+        if session.cookie_name.is_empty() || !session.cookie_name.chars().all(cookie_character) {
+            problems.push(format!(
+                "session.cookie_name should be letters, digits, and symbols like - and _, not {:?}",
+                session.cookie_name
+            ));
+        }
+        if session.lifetime_days <= 0 {
+            problems.push(format!(
+                "session.lifetime_days should be at least 1, not {}",
+                session.lifetime_days
+            ));
+        }
+
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(problems)),
+        }
+    }
+
+    /// The log level, which is checked to be valid when loaded
+    pub fn log_level(&self) -> log::Level {
+        self.log_level.parse().unwrap_or(log::Level::Info)
+    }
+}
+
+/// Overrides a setting with an environment variable, if it is set
+fn set<T: FromStr>(
+    setting: &mut T,
+    name: &'static str,
+    expected: &'static str,
+    env: &impl Fn(&str) -> Option<String>,
+) -> Result<(), ConfigError> {
+    if let Some(value) = env(name) {
+        *setting = value.parse().map_err(|_| ConfigError::Env {
+            name,
+            value,
+            expected,
+        })?;
+    }
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use cfg_if::cfg_if;
 pub mod app;
 pub mod auth;
+#[cfg(feature = "ssr")]
+pub mod config;
 pub mod error;
 pub mod fileserv;
 pub mod lang;
@@ -8,7 +10,7 @@ pub mod lang;
 cfg_if! { if #[cfg(feature = "ssr")] {
     use leptos::LeptosOptions;
     use surrealdb::Surreal;
-    use surrealdb::engine::remote::ws::Client;
+    use surrealdb::engine::any::Any;
     use axum::extract::FromRef;
 
     /// This takes advantage of Axum's SubStates feature by deriving FromRef. This is the only way to have more than one
@@ -16,7 +18,7 @@ cfg_if! { if #[cfg(feature = "ssr")] {
     #[derive(FromRef, Debug, Clone)]
     pub struct AppState{
         pub leptos_options: LeptosOptions,
-        pub db: Surreal<Client>
+        pub db: Surreal<Any>
     }
 }}
 
@@ -19,13 +19,11 @@ if #[cfg(feature = "ssr")] {
     use leptos_axum::{generate_route_list, LeptosRoutes, handle_server_fns_with_context};
     use leptos::{log, view, provide_context, get_configuration};
     use surrealdb::Surreal;
-    // use surrealdb::engine::remote::http::Client;
-    // use surrealdb::engine::remote::http::Http;
-    use surrealdb::engine::remote::ws::Client;
-    use surrealdb::engine::remote::ws::Ws;
-    use surrealdb::opt::auth::Namespace;
+    use surrealdb::engine::any::Any;
     use axum_session::{SessionConfig, SessionLayer, SessionStore};
     use axum_session_auth::{AuthSessionLayer, AuthConfig, SessionSurrealPool};
+    use std::{fmt::Display, process};
+    use webui::config::Config;
 
     async fn server_fn_handler(
         State(app_state): State<AppState>,
@@ -60,29 +58,45 @@ if #[cfg(feature = "ssr")] {
         handler(req).await.into_response()
     }
 
+    /// Stops the server at startup, explaining why
+    fn fail(context: &str, err: impl Display) -> ! {
+        eprintln!("{context}: {err}");
+        process::exit(1)
+    }
+
     #[tokio::main]
     async fn main() {
-        simple_logger::init_with_level(log::Level::Info).expect("couldn't initialize logging");
+        let config = Config::load().unwrap_or_else(|err| fail("Cannot configure the server", err)).set();
+        simple_logger::init_with_level(config.log_level())
+            .unwrap_or_else(|err| fail("Cannot initialize logging", err));
 
-        let db = Surreal::new::<Ws>("127.0.0.0:8080").await.expect("Cannot connect to DB");
-        db.signin(Namespace {
-            namespace:"hadijatek",
-            username:"hadijatek",
-            password:"hadijatek",
-        }).await.expect("Cannot sign in to hadijatek");
-        db.use_ns("hadijatek").use_db("auth").await.expect("Could not use auth DB");
+        // A database server, or a local database, like mem:// to try things out, see
+        // prelude::db::connect
+        let database = &config.database;
+        let db = prelude::db::connect(&database.address, &database.namespace, &database.username, &database.password)
+            .await
+            .unwrap_or_else(|err| fail(&format!("Cannot connect to the database at {}", database.address), err));
+        db.use_db(&database.name)
+            .await
+            .unwrap_or_else(|err| fail(&format!("Cannot use the database {}", database.name), err));
 
         // Auth section
-        let session_config = SessionConfig::default().with_table_name("axum_sessions");
+        let session_config = SessionConfig::default()
+            .with_table_name(config.session.table.as_str())
+            .with_cookie_name(config.session.cookie_name.as_str())
+            .with_lifetime(chrono::Duration::days(config.session.lifetime_days))
+            .with_secure(config.session.secure);
         let auth_config = AuthConfig::<String>::default();
-        let session_store = SessionStore::<SessionSurrealPool<Client>>::new(
+        let session_store = SessionStore::<SessionSurrealPool<Any>>::new(
             Some(db.clone().into()),
             session_config
-        ).await.unwrap();
-        session_store.initiate().await.unwrap();
+        ).await.unwrap_or_else(|err| fail("Cannot create the session store", err));
+        session_store.initiate().await.unwrap_or_else(|err| fail("Cannot set up the session store", err));
 
         // Setting this to None means we'll be using cargo-leptos and its env vars
-        let conf = get_configuration(None).await.unwrap();
+        let conf = get_configuration(None)
+            .await
+            .unwrap_or_else(|err| fail("Cannot read the Leptos configuration", err));
         let leptos_options = conf.leptos_options;
         let addr = leptos_options.site_addr;
         let routes = generate_route_list(|| view! { <App/> }).await;
@@ -100,7 +114,7 @@ if #[cfg(feature = "ssr")] {
         .route("/api/*fn_name", get(server_fn_handler).post(server_fn_handler))
         .leptos_routes_with_handler(routes, get(leptos_routes_handler) )
         .fallback(file_and_error_handler)
-        .layer(AuthSessionLayer::<User, String, SessionSurrealPool<Client>, Surreal<Client>>::new(Some(db))
+        .layer(AuthSessionLayer::<User, String, SessionSurrealPool<Any>, Surreal<Any>>::new(Some(db))
         .with_config(auth_config))
         .layer(SessionLayer::new(session_store))
         .with_state(app_state);
@@ -0,0 +1,118 @@
+//! Reading and validating the server configuration
+
+#![cfg(feature = "ssr")]
+
+use std::collections::HashMap;
+
+use webui::config::{Config, ConfigError};
+
+/// An environment with the given variables
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn defaults_are_valid() {
+    let config = Config::parse("").expect("an empty file is all defaults");
+    assert_eq!(config, Config::default());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn environment_overrides_file() {
+    let mut config = Config::parse(
+        r#"
+        log_level = "debug"
+
+        [database]
+        address = "mem://"
+        name = "users"
+
+        [session]
+        lifetime_days = 7
+        "#,
+    )
+    .expect("the file is valid");
+    config
+        .override_with(env(&[
+            ("HADIJATEK_DB_NAME", "players"),
+            ("HADIJATEK_SESSION_SECURE", "true"),
+        ]))
+        .expect("the variables are valid");
+
+    assert_eq!(config.log_level(), log::Level::Debug);
+    assert_eq!(config.database.address, "mem://");
+    assert_eq!(config.database.name, "players");
+    assert_eq!(config.database.namespace, "hadijatek");
+    assert_eq!(config.session.lifetime_days, 7);
+    assert!(config.session.secure);
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn unknown_settings_are_rejected() {
+    assert!(Config::parse("[database]\nadress = \"mem://\"").is_err());
+}
+
+#[test]
+fn malformed_variables_are_named() {
+    let mut config = Config::default();
+    let err = config
+        .override_with(env(&[("HADIJATEK_SESSION_LIFETIME_DAYS", "a week")]))
+        .expect_err("a week is not a number");
+    match err {
+        ConfigError::Env { name, value, .. } => {
+            assert_eq!(name, "HADIJATEK_SESSION_LIFETIME_DAYS");
+            assert_eq!(value, "a week");
+        }
+        err => panic!("unexpected error: {err}"),
+    }
+}
+
+#[test]
+fn every_problem_is_listed() {
+    let mut config = Config::default();
+    config.log_level = "loud".into();
+    config.database.address = "ftp://example.com".into();
+    config.database.password = String::new();
+    config.session.cookie_name = "my session".into();
+    config.session.lifetime_days = 0;
+
+    match config.validate() {
+        Err(ConfigError::Invalid(problems)) => {
+            let listed = |setting: &str| problems.iter().any(|p| p.starts_with(setting));
+            assert!(listed("log_level"));
+            assert!(listed("database.address"));
+            assert!(listed("session.cookie_name"));
+            assert!(listed("session.lifetime_days"));
+            // Local or unknown engines are not signed in to
+            assert!(!listed("database.password"));
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn servers_need_credentials() {
+    let mut config = Config::default();
+    config.database.username = String::new();
+    match config.validate() {
+        Err(ConfigError::Invalid(problems)) => {
+            assert_eq!(problems.len(), 1);
+            assert!(problems[0].starts_with("database.username"));
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn file_databases_need_the_file_feature() {
+    let mut config = Config::default();
+    config.database.address = "file://games".into();
+    let valid = config.validate().is_ok();
+    assert_eq!(valid, cfg!(feature = "file"));
+}